@@ -0,0 +1,243 @@
+use crate::{
+    application::{
+        errors::{EngineError, EngineResult},
+        events::{DamageCause, StateChange, StateChanges},
+        game_loop::{GameState, THROW_RANGE},
+    },
+    core::types::Direction,
+    domain::{
+        entity::{combat, effects, DealtDamage, EntityId, SpellKind},
+        world_position::WorldPosition,
+    },
+};
+
+use super::{adjacent_position, position_of, Action};
+
+/// Stamina spent by casting a spell.
+const CAST_STAMINA_COST: u64 = 1;
+
+/// Swings at whatever is next to the entity: a creature, or failing that a wall or door.
+#[derive(Debug, Clone)]
+pub struct Attack {
+    pub entity_id: EntityId,
+    pub direction: Direction,
+}
+
+enum AttackTarget {
+    Creature(EntityId),
+    Terrain(EntityId),
+}
+
+impl Attack {
+    fn target(&self, state: &GameState) -> EngineResult<AttackTarget> {
+        let pos = adjacent_position(state, self.entity_id, self.direction)?;
+        if let Some(defender_id) = state.get_creature_at(&pos, self.entity_id) {
+            Ok(AttackTarget::Creature(defender_id))
+        } else if let Some(terrain_id) = state.get_terrain_at(&pos) {
+            Ok(AttackTarget::Terrain(terrain_id))
+        } else {
+            Err(EngineError::invalid_target(
+                "there is nothing there to attack",
+            ))
+        }
+    }
+}
+
+impl Action for Attack {
+    fn actor(&self) -> Option<EntityId> {
+        Some(self.entity_id)
+    }
+
+    fn validate(&self, state: &GameState) -> EngineResult<()> {
+        self.target(state).map(|_| ())
+    }
+
+    fn perform(&self, state: &mut GameState) -> EngineResult<StateChanges> {
+        Ok(match self.target(state)? {
+            AttackTarget::Creature(defender_id) => state.attack(self.entity_id, defender_id),
+            AttackTarget::Terrain(terrain_id) => state.attack_terrain(self.entity_id, terrain_id),
+        })
+    }
+}
+
+/// Shoots the equipped ranged weapon towards a position.
+#[derive(Debug, Clone)]
+pub struct Fire {
+    pub shooter_id: EntityId,
+    pub target: WorldPosition,
+}
+
+impl Action for Fire {
+    fn actor(&self) -> Option<EntityId> {
+        Some(self.shooter_id)
+    }
+
+    fn validate(&self, state: &GameState) -> EngineResult<()> {
+        position_of(state, self.shooter_id)?;
+        if combat::get_ranged_weapon(&state.world, self.shooter_id).is_none() {
+            return Err(EngineError::not_allowed("no ranged weapon is equipped"));
+        }
+        Ok(())
+    }
+
+    fn perform(&self, state: &mut GameState) -> EngineResult<StateChanges> {
+        let (damage, range) = combat::get_ranged_weapon(&state.world, self.shooter_id)
+            .ok_or_else(|| EngineError::not_allowed("no ranged weapon is equipped"))?;
+        state.launch_projectile(self.shooter_id, None, self.target, range, damage)
+    }
+}
+
+/// Throws a carried item towards a position, where it lands.
+#[derive(Debug, Clone)]
+pub struct Throw {
+    pub thrower_id: EntityId,
+    pub item_id: EntityId,
+    pub target: WorldPosition,
+}
+
+impl Action for Throw {
+    fn actor(&self) -> Option<EntityId> {
+        Some(self.thrower_id)
+    }
+
+    fn validate(&self, state: &GameState) -> EngineResult<()> {
+        position_of(state, self.thrower_id)?;
+        state.get_entity(self.item_id)?;
+        let carried = state
+            .get_entity(self.thrower_id)?
+            .inventory()
+            .is_some_and(|inventory| inventory.contains(self.item_id));
+        if !carried {
+            return Err(EngineError::invalid_target("the item isn't being carried"));
+        }
+        Ok(())
+    }
+
+    fn perform(&self, state: &mut GameState) -> EngineResult<StateChanges> {
+        let damage = combat::get_thrown_damage(state.get_entity(self.item_id)?);
+        let thrower = state.get_entity_mut(self.thrower_id)?;
+        if let Some(inventory) = thrower.inventory_mut() {
+            inventory.remove(self.item_id);
+        }
+        if let Some(equipment) = thrower.equipment_mut() {
+            equipment.unequip(self.item_id);
+        }
+
+        state.launch_projectile(
+            self.thrower_id,
+            Some(self.item_id),
+            self.target,
+            THROW_RANGE,
+            damage,
+        )
+    }
+}
+
+/// Casts a known spell at a position, paying for it with mana.
+#[derive(Debug, Clone)]
+pub struct Cast {
+    pub caster_id: EntityId,
+    pub spell: SpellKind,
+    pub target: WorldPosition,
+}
+
+impl Action for Cast {
+    fn actor(&self) -> Option<EntityId> {
+        Some(self.caster_id)
+    }
+
+    fn validate(&self, state: &GameState) -> EngineResult<()> {
+        let spell = self.spell.get_spell();
+        let caster_pos = position_of(state, self.caster_id)?;
+        let caster = state.get_entity(self.caster_id)?;
+        if !caster.knows_spell(self.spell) {
+            return Err(EngineError::not_allowed("the spell isn't known"));
+        }
+        if caster
+            .status
+            .as_ref()
+            .is_none_or(|status| status.mana.current < spell.mana_cost)
+        {
+            return Err(EngineError::not_allowed("not enough mana"));
+        }
+        if !spell.can_target(&state.world, &caster_pos, &self.target) {
+            return Err(EngineError::invalid_target(
+                "the target is out of range or sight",
+            ));
+        }
+        Ok(())
+    }
+
+    fn perform(&self, state: &mut GameState) -> EngineResult<StateChanges> {
+        let mut changes = StateChanges::new();
+        let caster_id = self.caster_id;
+        let spell = self.spell.get_spell();
+        let caster_pos = position_of(state, caster_id)?;
+
+        let caster = state.get_entity_mut(caster_id)?;
+        if let Some(status) = caster.status.as_mut() {
+            status.mana.damage(spell.mana_cost);
+        }
+        caster.exert(CAST_STAMINA_COST);
+
+        let affected = spell.get_affected_positions(&state.world, &caster_pos, &self.target);
+        let targets: Vec<EntityId> = affected
+            .iter()
+            .flat_map(|pos| state.world.get_entities_by_pos(pos))
+            .filter(|e| e.is_creature())
+            .map(|e| e.id)
+            .collect();
+        let terrain: Vec<EntityId> = affected
+            .iter()
+            .filter_map(|pos| state.get_terrain_at(pos))
+            .collect();
+        changes.push(StateChange::SpellCast {
+            caster_id,
+            spell: self.spell,
+            target: self.target,
+            affected,
+        });
+
+        for target_id in targets {
+            let Some(target_entity) = state.world.get_entity(target_id) else {
+                continue;
+            };
+            let dealt: Vec<DealtDamage> = spell
+                .damage
+                .iter()
+                .map(|damage| DealtDamage {
+                    amount: combat::mitigate(
+                        target_entity,
+                        &damage.damage_type,
+                        damage.roll_with(&mut state.rng),
+                    ),
+                    damage_type: damage.damage_type.clone(),
+                })
+                .collect();
+
+            let mut status_effects = spell.get_status_effects(caster_id);
+            status_effects.extend(dealt.iter().filter(|dealt| dealt.amount > 0).filter_map(
+                |dealt| effects::get_on_hit_effect(&dealt.damage_type, Some(caster_id)),
+            ));
+
+            changes.extend(state.damage_entity(
+                target_id,
+                Some(caster_id),
+                DamageCause::Spell,
+                dealt,
+            ));
+            for effect in status_effects {
+                changes.extend(state.apply_effect(target_id, effect));
+            }
+        }
+        for terrain_id in terrain {
+            changes.extend(state.damage_terrain(
+                terrain_id,
+                Some(caster_id),
+                DamageCause::Spell,
+                &spell.damage,
+            ));
+        }
+        Ok(changes)
+    }
+}
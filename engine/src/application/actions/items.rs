@@ -0,0 +1,284 @@
+use crate::{
+    application::{
+        errors::{EngineError, EngineResult},
+        events::{StateChange, StateChanges},
+        game_loop::GameState,
+        turns::ACTION_ENERGY,
+    },
+    domain::entity::{Entity, EntityId, EntityKind, Equipment, Hand, ItemKind},
+};
+
+use super::{position_of, Action};
+
+/// Picking up, dropping and unequipping only take a moment.
+const QUICK_ACTION_ENERGY: u64 = ACTION_ENERGY / 2;
+
+/// Picks up an item lying where the entity stands.
+#[derive(Debug, Clone)]
+pub struct PickUp {
+    pub entity_id: EntityId,
+    pub item_id: EntityId,
+}
+
+impl Action for PickUp {
+    fn actor(&self) -> Option<EntityId> {
+        Some(self.entity_id)
+    }
+
+    fn validate(&self, state: &GameState) -> EngineResult<()> {
+        let entity = state.get_entity(self.entity_id)?;
+        let item = state.get_entity(self.item_id)?;
+        if !item.is_item() {
+            return Err(EngineError::invalid_target("that can't be picked up"));
+        }
+        if item.pos().is_none() || item.pos() != entity.pos() {
+            return Err(EngineError::invalid_target("the item is out of reach"));
+        }
+        if entity.inventory().is_none() {
+            return Err(EngineError::not_allowed("the entity can't carry items"));
+        }
+        Ok(())
+    }
+
+    fn energy_cost(&self, _state: &GameState) -> u64 {
+        QUICK_ACTION_ENERGY
+    }
+
+    fn perform(&self, state: &mut GameState) -> EngineResult<StateChanges> {
+        state
+            .get_entity_mut(self.entity_id)?
+            .inventory_mut()
+            .ok_or_else(|| EngineError::not_allowed("the entity can't carry items"))?
+            .add(self.item_id);
+        state.get_entity_mut(self.item_id)?.set_pos(None);
+        Ok(vec![StateChange::ItemPickedUp {
+            entity_id: self.entity_id,
+            item_id: self.item_id,
+        }])
+    }
+}
+
+/// Puts a carried item down where the entity stands, unequipping it first.
+#[derive(Debug, Clone)]
+pub struct DropItem {
+    pub entity_id: EntityId,
+    pub item_id: EntityId,
+}
+
+impl Action for DropItem {
+    fn actor(&self) -> Option<EntityId> {
+        Some(self.entity_id)
+    }
+
+    fn validate(&self, state: &GameState) -> EngineResult<()> {
+        // Items can only be dropped by someone standing on the map
+        position_of(state, self.entity_id)?;
+        if !carries(state.get_entity(self.entity_id)?, self.item_id) {
+            return Err(EngineError::invalid_target("the item isn't being carried"));
+        }
+        Ok(())
+    }
+
+    fn energy_cost(&self, _state: &GameState) -> u64 {
+        QUICK_ACTION_ENERGY
+    }
+
+    fn perform(&self, state: &mut GameState) -> EngineResult<StateChanges> {
+        let mut changes = StateChanges::new();
+        let (entity_id, item_id) = (self.entity_id, self.item_id);
+        let pos = position_of(state, entity_id)?;
+        let entity = state.get_entity_mut(entity_id)?;
+        if let Some(inventory) = entity.inventory_mut() {
+            inventory.remove(item_id);
+        }
+
+        let unequipped = entity
+            .equipment_mut()
+            .is_some_and(|equipment| equipment.unequip(item_id));
+        if unequipped {
+            changes.push(StateChange::ItemUnequipped { entity_id, item_id });
+        }
+        state.get_entity_mut(item_id)?.set_pos(Some(pos));
+        changes.push(StateChange::ItemDropped {
+            entity_id,
+            item_id,
+            pos,
+        });
+        Ok(changes)
+    }
+}
+
+/// Equips a carried weapon or piece of armor. Weapons can ask for a hand.
+#[derive(Debug, Clone)]
+pub struct Equip {
+    pub entity_id: EntityId,
+    pub item_id: EntityId,
+    pub hand: Option<Hand>,
+}
+
+impl Equip {
+    /// Puts the item on with the given equipment. Returns whether it fit.
+    fn equip(&self, kind: &ItemKind, equipment: &mut Equipment) -> bool {
+        match kind {
+            ItemKind::Weapon { two_handed, .. } => {
+                equipment.equip_weapon(self.item_id, *two_handed, self.hand)
+            }
+            ItemKind::Armor { .. } => self.hand.is_none() && equipment.equip_armor(self.item_id),
+            ItemKind::Key { .. } => false,
+        }
+    }
+
+    fn item_kind(&self, state: &GameState) -> EngineResult<ItemKind> {
+        match state.get_entity(self.item_id)?.kind() {
+            EntityKind::Item { kind } => Ok(kind.clone()),
+            _ => Err(EngineError::invalid_target("that isn't an item")),
+        }
+    }
+}
+
+impl Action for Equip {
+    fn actor(&self) -> Option<EntityId> {
+        Some(self.entity_id)
+    }
+
+    fn validate(&self, state: &GameState) -> EngineResult<()> {
+        let entity = state.get_entity(self.entity_id)?;
+        if !carries(entity, self.item_id) {
+            return Err(EngineError::invalid_target("the item isn't being carried"));
+        }
+        let kind = self.item_kind(state)?;
+        let equipment = entity
+            .equipment()
+            .ok_or_else(|| EngineError::not_allowed("the entity can't equip items"))?;
+        if equipment.is_equipped(self.item_id) {
+            return Err(EngineError::not_allowed("the item is already equipped"));
+        }
+        // Try it on a copy to see whether it fits without equipping anything yet
+        if !self.equip(&kind, &mut equipment.clone()) {
+            return Err(EngineError::not_allowed(
+                "the item can't be equipped like that",
+            ));
+        }
+        Ok(())
+    }
+
+    fn perform(&self, state: &mut GameState) -> EngineResult<StateChanges> {
+        let kind = self.item_kind(state)?;
+        let equipment = state
+            .get_entity_mut(self.entity_id)?
+            .equipment_mut()
+            .ok_or_else(|| EngineError::not_allowed("the entity can't equip items"))?;
+        if !self.equip(&kind, equipment) {
+            return Err(EngineError::not_allowed(
+                "the item can't be equipped like that",
+            ));
+        }
+        Ok(vec![StateChange::ItemEquipped {
+            entity_id: self.entity_id,
+            item_id: self.item_id,
+        }])
+    }
+}
+
+/// Takes off an equipped item, keeping it in the inventory.
+#[derive(Debug, Clone)]
+pub struct Unequip {
+    pub entity_id: EntityId,
+    pub item_id: EntityId,
+}
+
+impl Action for Unequip {
+    fn actor(&self) -> Option<EntityId> {
+        Some(self.entity_id)
+    }
+
+    fn validate(&self, state: &GameState) -> EngineResult<()> {
+        let equipped = state
+            .get_entity(self.entity_id)?
+            .equipment()
+            .is_some_and(|equipment| equipment.is_equipped(self.item_id));
+        if !equipped {
+            return Err(EngineError::invalid_target("the item isn't equipped"));
+        }
+        Ok(())
+    }
+
+    fn energy_cost(&self, _state: &GameState) -> u64 {
+        QUICK_ACTION_ENERGY
+    }
+
+    fn perform(&self, state: &mut GameState) -> EngineResult<StateChanges> {
+        if let Some(equipment) = state.get_entity_mut(self.entity_id)?.equipment_mut() {
+            equipment.unequip(self.item_id);
+        }
+        Ok(vec![StateChange::ItemUnequipped {
+            entity_id: self.entity_id,
+            item_id: self.item_id,
+        }])
+    }
+}
+
+/// Does whatever a carried item is for: equipping it, or taking it off if it
+/// is already equipped.
+#[derive(Debug, Clone)]
+pub struct UseItem {
+    pub entity_id: EntityId,
+    pub item_id: EntityId,
+}
+
+impl UseItem {
+    fn action(&self, state: &GameState) -> EngineResult<Box<dyn Action>> {
+        let (entity_id, item_id) = (self.entity_id, self.item_id);
+        let entity = state.get_entity(entity_id)?;
+        if !carries(entity, item_id) {
+            return Err(EngineError::invalid_target("the item isn't being carried"));
+        }
+        if entity
+            .equipment()
+            .is_some_and(|equipment| equipment.is_equipped(item_id))
+        {
+            return Ok(Box::new(Unequip { entity_id, item_id }));
+        }
+
+        match state.get_entity(item_id)?.kind() {
+            EntityKind::Item {
+                kind: ItemKind::Weapon { .. } | ItemKind::Armor { .. },
+            } => Ok(Box::new(Equip {
+                entity_id,
+                item_id,
+                hand: None,
+            })),
+            EntityKind::Item {
+                kind: ItemKind::Key { .. },
+            } => Err(EngineError::not_allowed(
+                "keys are used by walking into the door they open",
+            )),
+            _ => Err(EngineError::invalid_target("that isn't an item")),
+        }
+    }
+}
+
+impl Action for UseItem {
+    fn actor(&self) -> Option<EntityId> {
+        Some(self.entity_id)
+    }
+
+    fn validate(&self, state: &GameState) -> EngineResult<()> {
+        self.action(state)?.validate(state)
+    }
+
+    fn energy_cost(&self, state: &GameState) -> u64 {
+        self.action(state)
+            .map_or(ACTION_ENERGY, |action| action.energy_cost(state))
+    }
+
+    fn perform(&self, state: &mut GameState) -> EngineResult<StateChanges> {
+        self.action(state)?.perform(state)
+    }
+}
+
+fn carries(entity: &Entity, item_id: EntityId) -> bool {
+    entity
+        .inventory()
+        .is_some_and(|inventory| inventory.contains(item_id))
+}
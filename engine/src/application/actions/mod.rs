@@ -0,0 +1,123 @@
+//! Everything an entity can do on its turn. An action is checked with
+//! [`Action::validate`] before [`Action::perform`] changes anything, so a
+//! rejected action never leaves the game half updated.
+mod combat;
+mod items;
+mod movement;
+mod waiting;
+
+pub use combat::{Attack, Cast, Fire, Throw};
+pub use items::{DropItem, Equip, PickUp, Unequip, UseItem};
+pub use movement::{CloseDoor, Move, OpenDoor, TakeStairs};
+pub use waiting::{Rest, SkipTurn};
+
+use crate::{
+    application::{
+        errors::{EngineError, EngineResult},
+        events::{GameEvent, StateChanges},
+        game_loop::GameState,
+        turns::ACTION_ENERGY,
+    },
+    core::types::Direction,
+    domain::{
+        entity::{EntityId, StairDirection},
+        world_position::WorldPosition,
+    },
+};
+
+pub trait Action: std::fmt::Debug {
+    /// The entity taking the action, or `None` for whoever's turn it is.
+    fn actor(&self) -> Option<EntityId>;
+
+    /// Checks that the action can be carried out, without changing anything.
+    fn validate(&self, state: &GameState) -> EngineResult<()>;
+
+    /// Energy the action uses up. Entities gain energy by their speed and
+    /// act again once they have [`ACTION_ENERGY`].
+    fn energy_cost(&self, _state: &GameState) -> u64 {
+        ACTION_ENERGY
+    }
+
+    /// Carries out an action that passed validation.
+    fn perform(&self, state: &mut GameState) -> EngineResult<StateChanges>;
+}
+
+impl GameEvent {
+    pub fn into_action(self) -> Box<dyn Action> {
+        match self {
+            GameEvent::MoveByDirection(entity_id, direction) => Box::new(Move {
+                entity_id,
+                direction,
+            }),
+            GameEvent::Rest(entity_id) => Box::new(Rest { entity_id }),
+            GameEvent::PickUp(entity_id, item_id) => Box::new(PickUp { entity_id, item_id }),
+            GameEvent::DropItem(entity_id, item_id) => Box::new(DropItem { entity_id, item_id }),
+            GameEvent::Equip(entity_id, item_id, hand) => Box::new(Equip {
+                entity_id,
+                item_id,
+                hand,
+            }),
+            GameEvent::Unequip(entity_id, item_id) => Box::new(Unequip { entity_id, item_id }),
+            GameEvent::UseItem(entity_id, item_id) => Box::new(UseItem { entity_id, item_id }),
+            GameEvent::Attack(entity_id, direction) => Box::new(Attack {
+                entity_id,
+                direction,
+            }),
+            GameEvent::Cast(caster_id, spell, target) => Box::new(Cast {
+                caster_id,
+                spell,
+                target,
+            }),
+            GameEvent::OpenDoor(entity_id, direction) => Box::new(OpenDoor {
+                entity_id,
+                direction,
+            }),
+            GameEvent::CloseDoor(entity_id, direction) => Box::new(CloseDoor {
+                entity_id,
+                direction,
+            }),
+            GameEvent::Ascend(entity_id) => Box::new(TakeStairs {
+                entity_id,
+                direction: StairDirection::Up,
+            }),
+            GameEvent::Descend(entity_id) => Box::new(TakeStairs {
+                entity_id,
+                direction: StairDirection::Down,
+            }),
+            GameEvent::Fire { shooter, target } => Box::new(Fire {
+                shooter_id: shooter,
+                target,
+            }),
+            GameEvent::Throw {
+                thrower,
+                item_id,
+                target,
+            } => Box::new(Throw {
+                thrower_id: thrower,
+                item_id,
+                target,
+            }),
+            GameEvent::SkipTurn => Box::new(SkipTurn),
+        }
+    }
+}
+
+/// Where an entity is standing, for actions that need it to be on the map.
+fn position_of(state: &GameState, entity_id: EntityId) -> EngineResult<WorldPosition> {
+    state
+        .get_entity(entity_id)?
+        .pos()
+        .ok_or_else(|| EngineError::not_allowed("the entity is not on the map"))
+}
+
+/// The tile next to an entity in the given direction.
+fn adjacent_position(
+    state: &GameState,
+    entity_id: EntityId,
+    direction: Direction,
+) -> EngineResult<WorldPosition> {
+    state
+        .get_entity(entity_id)?
+        .get_pos_in_direction(direction)
+        .ok_or_else(|| EngineError::not_allowed("the entity is not on the map"))
+}
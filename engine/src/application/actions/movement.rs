@@ -0,0 +1,361 @@
+use crate::{
+    application::{
+        errors::{EngineError, EngineResult},
+        events::{StateChange, StateChanges},
+        game_loop::GameState,
+    },
+    core::types::Direction,
+    domain::{
+        dungeon::Depth,
+        entity::{ai::Ai, EntityId, EntityKind, ItemKind, KeyId, StairDirection},
+        world_position::WorldPosition,
+    },
+    prefabs,
+};
+
+use super::{adjacent_position, position_of, Action};
+
+/// Stamina spent by moving one tile.
+const MOVE_STAMINA_COST: u64 = 1;
+
+/// Steps one tile in a direction. Walking into another creature attacks it,
+/// and walking into a closed door tries to open it.
+#[derive(Debug, Clone)]
+pub struct Move {
+    pub entity_id: EntityId,
+    pub direction: Direction,
+}
+
+/// What a step turns into, depending on what is in the way.
+enum Step {
+    Attack(EntityId),
+    OpenDoor(EntityId),
+    Walk(WorldPosition),
+}
+
+impl Move {
+    fn step(&self, state: &GameState) -> EngineResult<Step> {
+        let pos = adjacent_position(state, self.entity_id, self.direction)?;
+        if let Some(defender_id) = state.get_creature_at(&pos, self.entity_id) {
+            return Ok(Step::Attack(defender_id));
+        }
+        let closed_door = state.get_door_at(&pos).filter(|id| {
+            state
+                .world
+                .get_entity(*id)
+                .is_some_and(|d| d.is_closed_door())
+        });
+        Ok(match closed_door {
+            Some(door_id) => Step::OpenDoor(door_id),
+            None => Step::Walk(pos),
+        })
+    }
+}
+
+impl Action for Move {
+    fn actor(&self) -> Option<EntityId> {
+        Some(self.entity_id)
+    }
+
+    fn validate(&self, state: &GameState) -> EngineResult<()> {
+        match self.step(state)? {
+            Step::Attack(_) => Ok(()),
+            Step::OpenDoor(door_id) => validate_open(state, self.entity_id, door_id),
+            Step::Walk(pos) => {
+                let entities_in_pos = state
+                    .world
+                    .get_entities_by_pos(&pos)
+                    .into_iter()
+                    .cloned()
+                    .collect();
+                if !state
+                    .get_entity(self.entity_id)?
+                    .can_move_to(entities_in_pos)
+                {
+                    return Err(EngineError::not_allowed("the way is blocked"));
+                }
+                Ok(())
+            }
+        }
+    }
+
+    fn perform(&self, state: &mut GameState) -> EngineResult<StateChanges> {
+        match self.step(state)? {
+            Step::Attack(defender_id) => Ok(state.attack(self.entity_id, defender_id)),
+            Step::OpenDoor(door_id) => open_door(state, self.entity_id, door_id),
+            Step::Walk(pos) => {
+                let entity = state.get_entity_mut(self.entity_id)?;
+                let from = entity.pos();
+                entity.set_pos(Some(pos));
+                entity.exert(MOVE_STAMINA_COST);
+                Ok(vec![StateChange::EntityMoved {
+                    entity_id: self.entity_id,
+                    from,
+                    to: Some(pos),
+                }])
+            }
+        }
+    }
+}
+
+/// Opens the door next to the entity, first unlocking it if the entity
+/// carries the right key.
+#[derive(Debug, Clone)]
+pub struct OpenDoor {
+    pub entity_id: EntityId,
+    pub direction: Direction,
+}
+
+impl OpenDoor {
+    fn door(&self, state: &GameState) -> EngineResult<EntityId> {
+        let pos = adjacent_position(state, self.entity_id, self.direction)?;
+        state
+            .get_door_at(&pos)
+            .ok_or_else(|| EngineError::invalid_target("there is no door there"))
+    }
+}
+
+impl Action for OpenDoor {
+    fn actor(&self) -> Option<EntityId> {
+        Some(self.entity_id)
+    }
+
+    fn validate(&self, state: &GameState) -> EngineResult<()> {
+        validate_open(state, self.entity_id, self.door(state)?)
+    }
+
+    fn perform(&self, state: &mut GameState) -> EngineResult<StateChanges> {
+        let door_id = self.door(state)?;
+        open_door(state, self.entity_id, door_id)
+    }
+}
+
+fn validate_open(state: &GameState, entity_id: EntityId, door_id: EntityId) -> EngineResult<()> {
+    let door = state.get_entity(door_id)?;
+    if !door.is_closed_door() {
+        return Err(EngineError::not_allowed("the door is already open"));
+    }
+    if door
+        .door_lock()
+        .is_some_and(|lock| !has_key(state, entity_id, lock))
+    {
+        return Err(EngineError::not_allowed("the door is locked"));
+    }
+    Ok(())
+}
+
+fn open_door(
+    state: &mut GameState,
+    entity_id: EntityId,
+    door_id: EntityId,
+) -> EngineResult<StateChanges> {
+    let mut changes = StateChanges::new();
+    let door = state.get_entity_mut(door_id)?;
+    if let Some(lock) = door.door_lock() {
+        if door.unlock_door(lock) {
+            changes.push(StateChange::DoorUnlocked { entity_id, door_id });
+        }
+    }
+
+    door.set_door_open(true);
+    changes.push(StateChange::DoorOpened { entity_id, door_id });
+    Ok(changes)
+}
+
+fn has_key(state: &GameState, entity_id: EntityId, lock: KeyId) -> bool {
+    state
+        .world
+        .get_inventory_items(entity_id)
+        .into_iter()
+        .any(|item| {
+            matches!(
+                item.kind,
+                EntityKind::Item {
+                    kind: ItemKind::Key { key_id }
+                } if key_id == lock
+            )
+        })
+}
+
+/// Shuts the door next to the entity.
+#[derive(Debug, Clone)]
+pub struct CloseDoor {
+    pub entity_id: EntityId,
+    pub direction: Direction,
+}
+
+impl CloseDoor {
+    fn door(&self, state: &GameState) -> EngineResult<EntityId> {
+        let pos = adjacent_position(state, self.entity_id, self.direction)?;
+        let door_id = state
+            .get_door_at(&pos)
+            .ok_or_else(|| EngineError::invalid_target("there is no door there"))?;
+
+        // Nothing can be shut on a creature standing in the doorway
+        if state.get_creature_at(&pos, self.entity_id).is_some() {
+            return Err(EngineError::not_allowed("something is in the doorway"));
+        }
+        Ok(door_id)
+    }
+}
+
+impl Action for CloseDoor {
+    fn actor(&self) -> Option<EntityId> {
+        Some(self.entity_id)
+    }
+
+    fn validate(&self, state: &GameState) -> EngineResult<()> {
+        if state.get_entity(self.door(state)?)?.is_closed_door() {
+            return Err(EngineError::not_allowed("the door is already closed"));
+        }
+        Ok(())
+    }
+
+    fn perform(&self, state: &mut GameState) -> EngineResult<StateChanges> {
+        let door_id = self.door(state)?;
+        state.get_entity_mut(door_id)?.set_door_open(false);
+        Ok(vec![StateChange::DoorClosed {
+            entity_id: self.entity_id,
+            door_id,
+        }])
+    }
+}
+
+/// Takes the player up or down the stairs they are standing on, along with
+/// any creatures right next to them. Levels are generated the first time
+/// they are entered and kept as they were left after that.
+#[derive(Debug, Clone)]
+pub struct TakeStairs {
+    pub entity_id: EntityId,
+    pub direction: StairDirection,
+}
+
+impl TakeStairs {
+    fn destination(&self, state: &GameState) -> Option<Depth> {
+        match self.direction {
+            StairDirection::Up => state.world.depth.checked_sub(1),
+            StairDirection::Down => state.world.depth.checked_add(1),
+        }
+    }
+}
+
+impl Action for TakeStairs {
+    fn actor(&self) -> Option<EntityId> {
+        Some(self.entity_id)
+    }
+
+    fn validate(&self, state: &GameState) -> EngineResult<()> {
+        if self.entity_id != state.world.player_id {
+            return Err(EngineError::not_allowed(
+                "only the player can take the stairs",
+            ));
+        }
+        let pos = position_of(state, self.entity_id)?;
+        let on_stairs = state.world.get_entities_by_pos(&pos).into_iter().any(
+            |e| matches!(e.kind, EntityKind::Stairs { direction, .. } if direction == self.direction),
+        );
+        if !on_stairs {
+            return Err(EngineError::not_allowed(
+                "there are no stairs that way here",
+            ));
+        }
+        if self.destination(state).is_none() {
+            return Err(EngineError::not_allowed("the stairs lead nowhere"));
+        }
+        Ok(())
+    }
+
+    fn perform(&self, state: &mut GameState) -> EngineResult<StateChanges> {
+        let entity_id = self.entity_id;
+        let pos = position_of(state, entity_id)?;
+        let from = state.world.depth;
+        let to = self
+            .destination(state)
+            .ok_or_else(|| EngineError::not_allowed("the stairs lead nowhere"))?;
+
+        let followers: Vec<EntityId> = state
+            .world
+            .entities
+            .values()
+            .filter(|e| e.id != entity_id && e.is_creature())
+            .filter(|e| e.pos().is_some_and(|p| p.chebyshev_distance(&pos) == 1))
+            .map(|e| e.id)
+            .collect();
+        let mut travellers = followers.clone();
+        travellers.push(entity_id);
+        let staying: Vec<EntityId> = state
+            .world
+            .query::<Ai>()
+            .map(|(e, _)| e.id)
+            .filter(|id| !travellers.contains(id))
+            .collect();
+
+        if !state.world.switch_level(to, &travellers) {
+            for entity in prefabs::levels::create_level(to, &mut state.rng) {
+                entity.spawn(&mut state.world);
+            }
+        }
+
+        // Arrive on the stairs leading back the way we came
+        let arrival = match self.direction {
+            StairDirection::Up => state.world.get_stairs(StairDirection::Down),
+            StairDirection::Down => state.world.get_stairs(StairDirection::Up),
+        }
+        .unwrap_or(pos);
+        state.get_entity_mut(entity_id)?.set_pos(Some(arrival));
+
+        // Followers crowd in around the stairs, and any without room are left
+        // behind where they stood. They are taken off the map first, so the
+        // spots they held on the old level don't count as taken here.
+        let origins: Vec<(EntityId, Option<WorldPosition>)> = followers
+            .iter()
+            .map(|&id| (id, state.world.get_entity(id).and_then(|e| e.pos())))
+            .collect();
+        for &(follower_id, _) in &origins {
+            state.get_entity_mut(follower_id)?.set_pos(None);
+        }
+        let mut arrived = Vec::new();
+        let mut left_behind = Vec::new();
+        for (follower_id, origin) in origins {
+            let follower = state.get_entity(follower_id)?;
+            let spot = arrival.surrounding().into_iter().find(|p| {
+                let entities_in_pos = state
+                    .world
+                    .get_entities_by_pos(p)
+                    .into_iter()
+                    .cloned()
+                    .collect();
+                follower.can_move_to(entities_in_pos)
+                    && state.get_creature_at(p, follower_id).is_none()
+            });
+            let follower = state.get_entity_mut(follower_id)?;
+            if let Some(spot) = spot {
+                follower.set_pos(Some(spot));
+                arrived.push(follower_id);
+            } else {
+                follower.set_pos(origin);
+                left_behind.push(follower_id);
+            }
+        }
+        state.world.send_to_level(from, &left_behind);
+
+        // Only creatures on the current level take turns. Those on the level
+        // left behind keep their place and energy for when it is visited
+        // again, and whoever is new here joins at the back.
+        let parked: Vec<EntityId> = staying.into_iter().chain(left_behind).collect();
+        state.turn_manager.park(from, &parked);
+        state.turn_manager.unpark(to);
+        let here: Vec<EntityId> = state.world.query::<Ai>().map(|(e, _)| e.id).collect();
+        for id in here {
+            state.turn_manager.add_entity(id);
+        }
+
+        tracing::info!(from, to, followers = ?arrived, "changed level");
+        Ok(vec![StateChange::LevelChanged {
+            entity_id,
+            from,
+            to,
+            followers: arrived,
+        }])
+    }
+}
@@ -0,0 +1,61 @@
+use crate::{
+    application::{
+        errors::{EngineError, EngineResult},
+        events::StateChanges,
+        game_loop::GameState,
+    },
+    domain::entity::EntityId,
+};
+
+use super::Action;
+
+/// Catches a breath, recovering stamina.
+#[derive(Debug, Clone)]
+pub struct Rest {
+    pub entity_id: EntityId,
+}
+
+impl Action for Rest {
+    fn actor(&self) -> Option<EntityId> {
+        Some(self.entity_id)
+    }
+
+    fn validate(&self, state: &GameState) -> EngineResult<()> {
+        state.get_entity(self.entity_id).map(|_| ())
+    }
+
+    fn perform(&self, state: &mut GameState) -> EngineResult<StateChanges> {
+        if let Some(status) = state.get_entity_mut(self.entity_id)?.status.as_mut() {
+            status.rest();
+        }
+        Ok(StateChanges::new())
+    }
+}
+
+/// Lets the turn pass. Skipping a turn is as good as resting for whoever's turn it is.
+#[derive(Debug, Clone)]
+pub struct SkipTurn;
+
+impl SkipTurn {
+    fn rest(state: &GameState) -> EngineResult<Rest> {
+        let entity_id = state
+            .turn_manager
+            .current_entity()
+            .ok_or_else(|| EngineError::not_allowed("nobody is taking a turn"))?;
+        Ok(Rest { entity_id })
+    }
+}
+
+impl Action for SkipTurn {
+    fn actor(&self) -> Option<EntityId> {
+        None
+    }
+
+    fn validate(&self, state: &GameState) -> EngineResult<()> {
+        Self::rest(state)?.validate(state)
+    }
+
+    fn perform(&self, state: &mut GameState) -> EngineResult<StateChanges> {
+        Self::rest(state)?.perform(state)
+    }
+}
@@ -0,0 +1,108 @@
+use std::fmt::Display;
+
+use serde::Serialize;
+use specta::Type;
+use ts_rs::TS;
+
+use crate::domain::entity::EntityId;
+
+/// Why the engine refused to carry out an action.
+#[derive(PartialEq, Eq, Clone, Debug, Serialize, TS, Type)]
+#[serde(tag = "type")]
+#[ts(export)]
+pub enum EngineError {
+    EntityNotFound {
+        entity_id: EntityId,
+    },
+    /// The action can't be taken in the current situation.
+    ActionNotAllowed {
+        reason: String,
+    },
+    NotEntitysTurn {
+        entity_id: EntityId,
+        /// Whoever's turn it actually is, if anyone's.
+        current: Option<EntityId>,
+    },
+    /// The entity or position the action was aimed at can't be used for it.
+    InvalidTarget {
+        reason: String,
+    },
+    /// The game state couldn't be accessed at all.
+    StateUnavailable,
+    /// The game couldn't be written out.
+    SaveFailed {
+        reason: String,
+    },
+    /// A saved game couldn't be read back.
+    InvalidSave {
+        reason: String,
+    },
+    /// The choices for a new game don't make a valid game or character.
+    InvalidConfig {
+        reason: String,
+    },
+}
+
+pub type EngineResult<T> = Result<T, EngineError>;
+
+impl EngineError {
+    pub fn not_allowed(reason: impl Into<String>) -> Self {
+        EngineError::ActionNotAllowed {
+            reason: reason.into(),
+        }
+    }
+
+    pub fn invalid_target(reason: impl Into<String>) -> Self {
+        EngineError::InvalidTarget {
+            reason: reason.into(),
+        }
+    }
+
+    pub fn save_failed(reason: impl ToString) -> Self {
+        EngineError::SaveFailed {
+            reason: reason.to_string(),
+        }
+    }
+
+    pub fn invalid_save(reason: impl ToString) -> Self {
+        EngineError::InvalidSave {
+            reason: reason.to_string(),
+        }
+    }
+
+    pub fn invalid_config(reason: impl Into<String>) -> Self {
+        EngineError::InvalidConfig {
+            reason: reason.into(),
+        }
+    }
+}
+
+impl Display for EngineError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            EngineError::EntityNotFound { entity_id } => {
+                write!(f, "entity #{} does not exist", entity_id)
+            }
+            EngineError::ActionNotAllowed { reason } => write!(f, "action not allowed: {}", reason),
+            EngineError::NotEntitysTurn {
+                entity_id,
+                current: Some(current),
+            } => write!(
+                f,
+                "it is entity #{}'s turn, not entity #{}'s",
+                current, entity_id
+            ),
+            EngineError::NotEntitysTurn {
+                entity_id,
+                current: None,
+            } => write!(f, "it is not entity #{}'s turn", entity_id),
+            EngineError::InvalidTarget { reason } => write!(f, "invalid target: {}", reason),
+            EngineError::StateUnavailable => write!(f, "the game state is unavailable"),
+            EngineError::SaveFailed { reason } => write!(f, "couldn't save the game: {}", reason),
+            EngineError::InvalidSave { reason } => write!(f, "couldn't load the game: {}", reason),
+            EngineError::InvalidConfig { reason } => write!(f, "invalid new game: {}", reason),
+        }
+    }
+}
+
+impl std::error::Error for EngineError {}
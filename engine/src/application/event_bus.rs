@@ -0,0 +1,101 @@
+use std::collections::{HashMap, HashSet};
+
+use crate::{
+    application::events::{DomainEvent, StateChange},
+    core::types::TurnNumber,
+    domain::{
+        entity::{EntityId, EntityKind},
+        world::World,
+    },
+};
+
+pub type SubscriptionId = u64;
+
+/// What a subscriber gets to look at along with an event.
+pub struct EventContext<'a> {
+    /// The world after the action that caused the event. Entities that were
+    /// removed are no longer in it.
+    pub world: &'a World,
+    pub turn: TurnNumber,
+    /// Every creature there was before the action, including ones it removed,
+    /// so they can still be told apart.
+    pub creatures: &'a HashMap<EntityId, EntityKind>,
+    /// What the player could see before the action.
+    pub seen_before: &'a HashSet<EntityId>,
+}
+
+impl EventContext<'_> {
+    /// What kind of entity `entity_id` is or was, if it can still be told.
+    pub fn kind_of(&self, entity_id: EntityId) -> Option<&EntityKind> {
+        self.world
+            .get_entity(entity_id)
+            .map(|e| &e.kind)
+            .or(self.creatures.get(&entity_id))
+    }
+
+    /// Whether the player saw `entity_id` while the action happened, either
+    /// before it or after. The player always knows about themselves.
+    pub fn player_sees(&self, entity_id: EntityId) -> bool {
+        entity_id == self.world.player_id
+            || self.seen_before.contains(&entity_id)
+            || self.world.get_entity(entity_id).is_some_and(|e| e.visible)
+    }
+}
+
+/// Something that reacts to what happens in the game, such as the message
+/// log, achievements, sound triggers or quests.
+pub trait Subscriber: Send {
+    /// Called for every event, after the action that caused it has been
+    /// carried out.
+    fn notify(&mut self, context: &EventContext, event: &DomainEvent);
+}
+
+impl<F> Subscriber for F
+where
+    F: FnMut(&EventContext, &DomainEvent) + Send,
+{
+    fn notify(&mut self, context: &EventContext, event: &DomainEvent) {
+        self(context, event)
+    }
+}
+
+/// Hands what every action and turn did to whoever subscribed, so they can
+/// react without the game loop knowing about them.
+#[derive(Default)]
+pub struct EventBus {
+    subscribers: Vec<(SubscriptionId, Box<dyn Subscriber>)>,
+    next_id: SubscriptionId,
+}
+
+impl EventBus {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds a subscriber. Returns an id it can be unsubscribed with.
+    pub fn subscribe(&mut self, subscriber: impl Subscriber + 'static) -> SubscriptionId {
+        let id = self.next_id;
+        self.next_id += 1;
+        self.subscribers.push((id, Box::new(subscriber)));
+        id
+    }
+
+    /// Removes a subscriber. Returns whether it was subscribed.
+    pub fn unsubscribe(&mut self, id: SubscriptionId) -> bool {
+        let count = self.subscribers.len();
+        self.subscribers
+            .retain(|(subscriber_id, _)| *subscriber_id != id);
+        self.subscribers.len() != count
+    }
+
+    /// Notifies every subscriber of the event behind each change, in the order
+    /// they happened.
+    pub fn publish(&mut self, context: &EventContext, changes: &[StateChange]) {
+        for change in changes {
+            let event = DomainEvent::from(change.clone());
+            for (_, subscriber) in self.subscribers.iter_mut() {
+                subscriber.notify(context, &event);
+            }
+        }
+    }
+}
@@ -1,7 +1,11 @@
 // src/application/events.rs
 use crate::{
     core::types::Direction,
-    domain::{entity::EntityId, world::World, world_position::WorldPosition},
+    domain::{
+        dungeon::Depth,
+        entity::{AttackRoll, DamageType, EffectKind, EntityId, Hand, SpellKind, StatusEffect},
+        world_position::WorldPosition,
+    },
 };
 
 #[derive(Debug, Clone, serde::Serialize)]
@@ -17,14 +21,299 @@ pub enum StateChange {
     TurnStarted {
         entity_id: EntityId,
     },
+    ItemPickedUp {
+        entity_id: EntityId,
+        item_id: EntityId,
+    },
+    ItemDropped {
+        entity_id: EntityId,
+        item_id: EntityId,
+        pos: WorldPosition,
+    },
+    ItemEquipped {
+        entity_id: EntityId,
+        item_id: EntityId,
+    },
+    ItemUnequipped {
+        entity_id: EntityId,
+        item_id: EntityId,
+    },
+    AttackRolled {
+        attacker_id: EntityId,
+        defender_id: EntityId,
+        roll: AttackRoll,
+    },
+    EntityDamaged {
+        entity_id: EntityId,
+        source: Option<EntityId>,
+        cause: DamageCause,
+        amount: u64,
+        damage_type: DamageType,
+    },
+    EntityHealed {
+        entity_id: EntityId,
+        amount: u64,
+    },
+    EntityDied {
+        entity_id: EntityId,
+    },
+    EffectApplied {
+        entity_id: EntityId,
+        effect: StatusEffect,
+    },
+    EffectTicked {
+        entity_id: EntityId,
+        effect: EffectKind,
+        remaining_turns: u64,
+    },
+    EffectExpired {
+        entity_id: EntityId,
+        effect: EffectKind,
+    },
+    ProjectileFired {
+        shooter_id: EntityId,
+        /// The item that was thrown, if it wasn't a shot from a ranged weapon.
+        item_id: Option<EntityId>,
+        path: Vec<WorldPosition>,
+        hit: Option<EntityId>,
+    },
+    SpellCast {
+        caster_id: EntityId,
+        spell: SpellKind,
+        target: WorldPosition,
+        affected: Vec<WorldPosition>,
+    },
+    TurnSkipped {
+        entity_id: EntityId,
+    },
+    DoorOpened {
+        entity_id: EntityId,
+        door_id: EntityId,
+    },
+    DoorClosed {
+        entity_id: EntityId,
+        door_id: EntityId,
+    },
+    DoorUnlocked {
+        entity_id: EntityId,
+        door_id: EntityId,
+    },
+    TerrainDestroyed {
+        entity_id: EntityId,
+        pos: Option<WorldPosition>,
+    },
+    LevelChanged {
+        entity_id: EntityId,
+        from: Depth,
+        to: Depth,
+        /// Creatures that were next to the entity and came along.
+        followers: Vec<EntityId>,
+    },
+}
+
+/// What dealt a bit of damage.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, serde::Serialize)]
+pub enum DamageCause {
+    /// A hit from a melee attack, a shot or a thrown item.
+    Attack,
+    Spell,
+    /// A status effect wearing the entity down, such as burning or poison.
+    Effect,
+}
+
+impl StateChange {
+    /// The entities the change is about, such as whoever acted and whatever
+    /// they acted on.
+    pub fn entities(&self) -> Vec<EntityId> {
+        match self {
+            StateChange::EntityMoved { entity_id, .. }
+            | StateChange::TurnEnded { entity_id }
+            | StateChange::TurnStarted { entity_id }
+            | StateChange::EntityHealed { entity_id, .. }
+            | StateChange::EntityDied { entity_id }
+            | StateChange::EffectApplied { entity_id, .. }
+            | StateChange::EffectTicked { entity_id, .. }
+            | StateChange::EffectExpired { entity_id, .. }
+            | StateChange::TurnSkipped { entity_id }
+            | StateChange::TerrainDestroyed { entity_id, .. } => vec![*entity_id],
+            StateChange::ItemPickedUp { entity_id, item_id }
+            | StateChange::ItemDropped {
+                entity_id, item_id, ..
+            }
+            | StateChange::ItemEquipped { entity_id, item_id }
+            | StateChange::ItemUnequipped { entity_id, item_id } => vec![*entity_id, *item_id],
+            StateChange::DoorOpened { entity_id, door_id }
+            | StateChange::DoorClosed { entity_id, door_id }
+            | StateChange::DoorUnlocked { entity_id, door_id } => vec![*entity_id, *door_id],
+            StateChange::AttackRolled {
+                attacker_id,
+                defender_id,
+                ..
+            } => vec![*attacker_id, *defender_id],
+            StateChange::EntityDamaged {
+                entity_id, source, ..
+            } => std::iter::once(*entity_id).chain(*source).collect(),
+            StateChange::ProjectileFired {
+                shooter_id, hit, ..
+            } => std::iter::once(*shooter_id).chain(*hit).collect(),
+            StateChange::SpellCast { caster_id, .. } => vec![*caster_id],
+            StateChange::LevelChanged {
+                entity_id,
+                followers,
+                ..
+            } => std::iter::once(*entity_id)
+                .chain(followers.iter().copied())
+                .collect(),
+        }
+    }
 }
 
 pub type StateChanges = Vec<StateChange>;
 
+/// What happened in the game, as published on the event bus. The happenings
+/// most subscribers care about have a variant of their own, and every other
+/// change is passed on as it is.
+#[derive(Debug, Clone)]
+pub enum DomainEvent {
+    Moved {
+        entity_id: EntityId,
+        from: Option<WorldPosition>,
+        to: Option<WorldPosition>,
+    },
+    Attacked {
+        attacker_id: EntityId,
+        defender_id: EntityId,
+        roll: AttackRoll,
+    },
+    Died {
+        entity_id: EntityId,
+    },
+    Damaged {
+        entity_id: EntityId,
+        source: Option<EntityId>,
+        cause: DamageCause,
+        amount: u64,
+        damage_type: DamageType,
+    },
+    PickedUp {
+        entity_id: EntityId,
+        item_id: EntityId,
+    },
+    LevelChanged {
+        entity_id: EntityId,
+        from: Depth,
+        to: Depth,
+        followers: Vec<EntityId>,
+    },
+    Other(StateChange),
+}
+
+impl DomainEvent {
+    /// The entities the event is about, such as whoever acted and whatever
+    /// they acted on.
+    pub fn entities(&self) -> Vec<EntityId> {
+        match self {
+            DomainEvent::Moved { entity_id, .. } | DomainEvent::Died { entity_id } => {
+                vec![*entity_id]
+            }
+            DomainEvent::Attacked {
+                attacker_id,
+                defender_id,
+                ..
+            } => vec![*attacker_id, *defender_id],
+            DomainEvent::Damaged {
+                entity_id, source, ..
+            } => std::iter::once(*entity_id).chain(*source).collect(),
+            DomainEvent::PickedUp { entity_id, item_id } => vec![*entity_id, *item_id],
+            DomainEvent::LevelChanged {
+                entity_id,
+                followers,
+                ..
+            } => std::iter::once(*entity_id)
+                .chain(followers.iter().copied())
+                .collect(),
+            DomainEvent::Other(change) => change.entities(),
+        }
+    }
+}
+
+impl From<StateChange> for DomainEvent {
+    fn from(change: StateChange) -> Self {
+        match change {
+            StateChange::EntityMoved {
+                entity_id,
+                from,
+                to,
+            } => DomainEvent::Moved {
+                entity_id,
+                from,
+                to,
+            },
+            StateChange::AttackRolled {
+                attacker_id,
+                defender_id,
+                roll,
+            } => DomainEvent::Attacked {
+                attacker_id,
+                defender_id,
+                roll,
+            },
+            StateChange::EntityDamaged {
+                entity_id,
+                source,
+                cause,
+                amount,
+                damage_type,
+            } => DomainEvent::Damaged {
+                entity_id,
+                source,
+                cause,
+                amount,
+                damage_type,
+            },
+            StateChange::EntityDied { entity_id } => DomainEvent::Died { entity_id },
+            StateChange::ItemPickedUp { entity_id, item_id } => {
+                DomainEvent::PickedUp { entity_id, item_id }
+            }
+            StateChange::LevelChanged {
+                entity_id,
+                from,
+                to,
+                followers,
+            } => DomainEvent::LevelChanged {
+                entity_id,
+                from,
+                to,
+                followers,
+            },
+            change => DomainEvent::Other(change),
+        }
+    }
+}
+
 #[derive(Debug, Clone)]
 pub enum GameEvent {
     MoveByDirection(EntityId, Direction),
+    Rest(EntityId),
+    PickUp(EntityId, EntityId),
+    DropItem(EntityId, EntityId),
+    Equip(EntityId, EntityId, Option<Hand>),
+    Unequip(EntityId, EntityId),
+    UseItem(EntityId, EntityId),
+    Attack(EntityId, Direction),
+    Cast(EntityId, SpellKind, WorldPosition),
+    OpenDoor(EntityId, Direction),
+    CloseDoor(EntityId, Direction),
+    Ascend(EntityId),
+    Descend(EntityId),
+    Fire {
+        shooter: EntityId,
+        target: WorldPosition,
+    },
+    Throw {
+        thrower: EntityId,
+        item_id: EntityId,
+        target: WorldPosition,
+    },
     SkipTurn,
 }
-
-impl GameEvent {}
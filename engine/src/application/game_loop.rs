@@ -1,15 +1,39 @@
-use std::ops::{Deref, DerefMut};
+use std::collections::{HashMap, HashSet};
+
+use serde::{Deserialize, Serialize};
 
 use crate::{
-    application::{events::GameEvent, turns::TurnManager},
-    core::types::Direction,
+    application::{
+        actions::Action,
+        errors::{EngineError, EngineResult},
+        event_bus::{EventBus, EventContext},
+        events::{DamageCause, GameEvent, StateChange, StateChanges},
+        messages::SharedMessageLog,
+        turns::{TurnManager, ACTION_ENERGY},
+    },
+    core::rng::{self, GameRng, Seed},
     domain::{
-        entity::{self, EntityId},
+        entity::{
+            combat, effects, Damage, DealtDamage, EffectKind, Entity, EntityBuilder, EntityId,
+            EntityKind, StatusEffect,
+        },
+        projectile::ProjectilePath,
         world::World,
         world_position::WorldPosition,
     },
 };
 
+/// Stamina spent by a melee attack.
+const ATTACK_STAMINA_COST: u64 = 2;
+/// Stamina spent by shooting or throwing.
+const RANGED_STAMINA_COST: u64 = 1;
+/// How far anything can be thrown.
+pub(crate) const THROW_RANGE: i32 = 5;
+/// Most turns other entities can take between two player actions, in case
+/// nothing ever hands control back to the player.
+const MAX_TURNS_BETWEEN_ACTIONS: usize = 1000;
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
 pub enum ProcessState {
     ProcessingTurns,
     WaitingForPlayer,
@@ -19,84 +43,616 @@ pub struct GameState {
     pub world: World,
     pub turn_manager: TurnManager,
     pub process_state: ProcessState,
+    /// Subscribed to `events`, so it hears about everything that happens.
+    pub messages: SharedMessageLog,
+    pub events: EventBus,
+    /// The seed the game was started from.
+    pub seed: Seed,
+    /// Everything left to chance in the game is decided by this.
+    pub rng: GameRng,
 }
 
 impl GameState {
-    pub fn new(world: World, turn_manager: TurnManager) -> Self {
+    pub fn new(world: World, turn_manager: TurnManager, seed: Seed) -> Self {
+        let messages = SharedMessageLog::default();
         Self {
             world,
             turn_manager,
             process_state: ProcessState::ProcessingTurns,
+            events: event_bus_with(&messages),
+            messages,
+            seed,
+            rng: rng::seeded(seed),
+        }
+    }
+
+    /// Spawns an entity into the current level. Entities that can act join the
+    /// turn order straight away.
+    pub fn spawn(&mut self, builder: EntityBuilder) -> EntityId {
+        let can_act = builder.can_act();
+        let entity_id = builder.spawn(&mut self.world);
+        if can_act {
+            self.turn_manager.add_entity(entity_id);
+        }
+        entity_id
+    }
+
+    /// Carries out an action for the entity whose turn it is, which ends that turn.
+    pub fn handle_event(&mut self, event: GameEvent) -> EngineResult<StateChanges> {
+        self.perform_action(event.into_action().as_ref())
+    }
+
+    /// Validates and performs an action, then ends the actor's turn and spends
+    /// the energy the action took. Nothing changes if the action is rejected.
+    pub fn perform_action(&mut self, action: &dyn Action) -> EngineResult<StateChanges> {
+        let _span = tracing::debug_span!(
+            "perform_action",
+            turn = self.turn_manager.turn_number(),
+            ?action
+        )
+        .entered();
+        let result = self.validate_turn(action.actor()).and_then(|entity_id| {
+            action.validate(self)?;
+            let cost = action.energy_cost(self);
+            self.logged(|state| {
+                let mut changes = action.perform(state)?;
+                state.turn_manager.spend_energy(entity_id, cost);
+                state.process_state = ProcessState::ProcessingTurns;
+                changes.push(StateChange::TurnEnded { entity_id });
+                Ok(changes)
+            })
+        });
+        match &result {
+            Ok(changes) => tracing::debug!(?changes, "action performed"),
+            Err(error) => tracing::debug!(%error, "action rejected"),
+        }
+        // Moving or opening and closing doors changes what the player can see
+        self.world.update_visibility();
+        result
+    }
+
+    /// Checks that the actor is the one whose turn it is, and that the player
+    /// only acts while the game is waiting for them. Returns the actor, which
+    /// is whoever's turn it is when the action doesn't name one.
+    fn validate_turn(&self, actor: Option<EntityId>) -> EngineResult<EntityId> {
+        let current = self.turn_manager.current_entity();
+        let entity_id = actor
+            .or(current)
+            .ok_or_else(|| EngineError::not_allowed("nobody is taking a turn"))?;
+
+        if current != Some(entity_id) {
+            return Err(EngineError::NotEntitysTurn { entity_id, current });
+        }
+        let waiting_for_player = matches!(self.process_state, ProcessState::WaitingForPlayer);
+        if entity_id == self.world.player_id && !waiting_for_player {
+            return Err(EngineError::not_allowed("turns are still being processed"));
+        }
+        Ok(entity_id)
+    }
+
+    /// Runs `f` and publishes the changes it makes to the event bus.
+    fn logged(
+        &mut self,
+        f: impl FnOnce(&mut Self) -> EngineResult<StateChanges>,
+    ) -> EngineResult<StateChanges> {
+        // Creatures can be removed from the world while the changes happen, so
+        // remember what they were to still be able to name them
+        let creatures: HashMap<EntityId, EntityKind> = self
+            .world
+            .entities
+            .values()
+            .filter(|e| e.is_creature())
+            .map(|e| (e.id, e.kind.clone()))
+            .collect();
+        let seen_before: HashSet<EntityId> = self
+            .world
+            .entities
+            .values()
+            .filter(|e| e.visible)
+            .map(|e| e.id)
+            .collect();
+
+        let changes = f(self)?;
+        let context = EventContext {
+            world: &self.world,
+            turn: self.turn_manager.turn_number(),
+            creatures: &creatures,
+            seen_before: &seen_before,
+        };
+        self.events.publish(&context, &changes);
+        Ok(changes)
+    }
+
+    pub(crate) fn get_entity(&self, entity_id: EntityId) -> EngineResult<&Entity> {
+        self.world
+            .get_entity(entity_id)
+            .ok_or(EngineError::EntityNotFound { entity_id })
+    }
+
+    pub(crate) fn get_entity_mut(&mut self, entity_id: EntityId) -> EngineResult<&mut Entity> {
+        self.world
+            .get_entity_mut(entity_id)
+            .ok_or(EngineError::EntityNotFound { entity_id })
+    }
+
+    pub(crate) fn get_door_at(&self, pos: &WorldPosition) -> Option<EntityId> {
+        self.world
+            .get_entities_by_pos(pos)
+            .into_iter()
+            .find(|e| e.is_door())
+            .map(|e| e.id)
+    }
+
+    /// Gets a creature standing at `pos`, other than `except`.
+    pub(crate) fn get_creature_at(
+        &self,
+        pos: &WorldPosition,
+        except: EntityId,
+    ) -> Option<EntityId> {
+        self.world
+            .get_entities_by_pos(pos)
+            .into_iter()
+            .find(|e| e.is_creature() && e.id != except)
+            .map(|e| e.id)
+    }
+
+    pub(crate) fn get_terrain_at(&self, pos: &WorldPosition) -> Option<EntityId> {
+        self.world
+            .get_entities_by_pos(pos)
+            .into_iter()
+            .find(|e| e.is_terrain())
+            .map(|e| e.id)
+    }
+
+    /// Swings at a wall or door with whatever the attacker has in hand.
+    pub(crate) fn attack_terrain(
+        &mut self,
+        attacker_id: EntityId,
+        terrain_id: EntityId,
+    ) -> StateChanges {
+        let damage = combat::get_attack_damage(&self.world, attacker_id);
+        if let Some(attacker) = self.world.get_entity_mut(attacker_id) {
+            attacker.exert(ATTACK_STAMINA_COST);
         }
+        self.damage_terrain(terrain_id, Some(attacker_id), DamageCause::Attack, &damage)
     }
 
-    pub fn handle_event(&mut self, event: GameEvent) -> () {
-        match event {
-            GameEvent::MoveByDirection(entity_id, dir) => {
-                let entity = self.world.get_entity(entity_id);
-                let old_pos = entity.and_then(|e| e.pos());
-
-                let new_pos = match (old_pos, dir) {
-                    (Some(pos), Direction::North) => Some(WorldPosition::new(pos.x, pos.y - 1)),
-                    (Some(pos), Direction::South) => Some(WorldPosition::new(pos.x, pos.y + 1)),
-                    (Some(pos), Direction::East) => Some(WorldPosition::new(pos.x + 1, pos.y)),
-                    (Some(pos), Direction::West) => Some(WorldPosition::new(pos.x - 1, pos.y)),
-                    (None, _) => None,
-                };
-
-                if let Some(new_pos) = new_pos {
-                    let entities_in_pos = self
-                        .world
-                        .get_entities_by_pos(&new_pos)
-                        .to_vec()
-                        .into_iter()
-                        .cloned()
-                        .collect();
-                    if let Some(entity) = self.world.get_entity_mut(entity_id) {
-                        let can_move = entity.can_move_to(entities_in_pos);
-
-                        if can_move {
-                            entity.set_pos(Some(new_pos));
-                        }
-                    }
+    pub(crate) fn damage_terrain(
+        &mut self,
+        terrain_id: EntityId,
+        source: Option<EntityId>,
+        cause: DamageCause,
+        damage: &[Damage],
+    ) -> StateChanges {
+        let dealt =
+            combat::resolve_terrain_damage_with(&self.world, terrain_id, damage, &mut self.rng);
+        self.damage_entity(terrain_id, source, cause, dealt)
+    }
+
+    pub(crate) fn attack(&mut self, attacker_id: EntityId, defender_id: EntityId) -> StateChanges {
+        let damage = combat::get_attack_damage(&self.world, attacker_id);
+        if let Some(attacker) = self.world.get_entity_mut(attacker_id) {
+            attacker.exert(ATTACK_STAMINA_COST);
+        }
+        self.resolve_hit(attacker_id, defender_id, damage)
+    }
+
+    /// Rolls an attack with the given damage and applies the outcome to the defender.
+    fn resolve_hit(
+        &mut self,
+        attacker_id: EntityId,
+        defender_id: EntityId,
+        damage: Vec<Damage>,
+    ) -> StateChanges {
+        let mut changes = StateChanges::new();
+        let Some(result) = combat::resolve_attack_with(
+            &self.world,
+            attacker_id,
+            defender_id,
+            damage,
+            &mut self.rng,
+        ) else {
+            return changes;
+        };
+
+        changes.push(StateChange::AttackRolled {
+            attacker_id,
+            defender_id,
+            roll: result.roll,
+        });
+        let on_hit_effects: Vec<StatusEffect> = result
+            .damage
+            .iter()
+            .filter(|dealt| dealt.amount > 0)
+            .filter_map(|dealt| effects::get_on_hit_effect(&dealt.damage_type, Some(attacker_id)))
+            .collect();
+        changes.extend(self.damage_entity(
+            defender_id,
+            Some(attacker_id),
+            DamageCause::Attack,
+            result.damage,
+        ));
+        for effect in on_hit_effects {
+            changes.extend(self.apply_effect(defender_id, effect));
+        }
+        changes
+    }
+
+    /// Previews where a shot from the entity's ranged weapon, or a throw if it
+    /// has none, would go. Used for the targeting reticle, so the path stops
+    /// where it goes out of the player's sight, and what it would hit is only
+    /// told if the player can see it.
+    pub fn preview_projectile(
+        &self,
+        entity_id: EntityId,
+        target: WorldPosition,
+    ) -> EngineResult<ProjectilePath> {
+        let from = self
+            .get_entity(entity_id)?
+            .pos()
+            .ok_or_else(|| EngineError::not_allowed("the entity is not on the map"))?;
+        let range = combat::get_ranged_weapon(&self.world, entity_id)
+            .map_or(THROW_RANGE, |(_, range)| range);
+        let mut preview = self.world.trace_projectile(&from, &target, range);
+
+        let visible = self.world.player_visible_positions();
+        if let Some(hidden) = preview.path.iter().position(|pos| !visible.contains(pos)) {
+            preview.path.truncate(hidden);
+            preview.hit = None;
+            preview.end = preview.path.last().copied().unwrap_or(from);
+        }
+        preview.hit = preview.hit.filter(|&hit| {
+            self.world
+                .get_entity(hit)
+                .and_then(|e| e.pos())
+                .is_some_and(|pos| visible.contains(&pos))
+        });
+        Ok(preview)
+    }
+
+    /// Sends a shot or thrown item towards `target`. A creature in the way gets
+    /// an attack made against it, and a thrown item lands where the flight ends.
+    pub(crate) fn launch_projectile(
+        &mut self,
+        shooter_id: EntityId,
+        item_id: Option<EntityId>,
+        target: WorldPosition,
+        range: i32,
+        damage: Vec<Damage>,
+    ) -> EngineResult<StateChanges> {
+        let mut changes = StateChanges::new();
+        let from = self
+            .get_entity(shooter_id)?
+            .pos()
+            .ok_or_else(|| EngineError::not_allowed("the entity is not on the map"))?;
+
+        let projectile = self.world.trace_projectile(&from, &target, range);
+        if let Some(shooter) = self.world.get_entity_mut(shooter_id) {
+            shooter.exert(RANGED_STAMINA_COST);
+        }
+        if let Some(item) = item_id.and_then(|id| self.world.get_entity_mut(id)) {
+            item.set_pos(Some(projectile.end));
+        }
+        changes.push(StateChange::ProjectileFired {
+            shooter_id,
+            item_id,
+            path: projectile.path,
+            hit: projectile.hit,
+        });
+
+        let defender_id = projectile
+            .hit
+            .filter(|id| self.world.get_entity(*id).is_some_and(|e| e.is_creature()));
+        if let Some(defender_id) = defender_id {
+            changes.extend(self.resolve_hit(shooter_id, defender_id, damage));
+        } else if let Some(terrain_id) = projectile
+            .hit
+            .filter(|id| self.world.get_entity(*id).is_some_and(|e| e.is_terrain()))
+        {
+            changes.extend(self.damage_terrain(
+                terrain_id,
+                Some(shooter_id),
+                DamageCause::Attack,
+                &damage,
+            ));
+        }
+        Ok(changes)
+    }
+
+    pub(crate) fn damage_entity(
+        &mut self,
+        entity_id: EntityId,
+        source: Option<EntityId>,
+        cause: DamageCause,
+        damage: Vec<DealtDamage>,
+    ) -> StateChanges {
+        let mut changes = StateChanges::new();
+        let Some(entity) = self.world.get_entity_mut(entity_id) else {
+            return changes;
+        };
+        // Only entities with health can be hurt
+        let Some(status) = entity.status.as_mut() else {
+            return changes;
+        };
+
+        for dealt in damage {
+            let amount = status.health.damage(dealt.amount);
+            changes.push(StateChange::EntityDamaged {
+                entity_id,
+                source,
+                cause,
+                amount,
+                damage_type: dealt.damage_type,
+            });
+        }
+
+        if entity.is_dead() {
+            if entity.is_terrain() {
+                changes.extend(self.destroy_terrain(entity_id));
+            } else {
+                changes.extend(self.kill_entity(entity_id));
+            }
+        }
+        changes
+    }
+
+    /// Turns a wall or door worn down to nothing into rubble.
+    fn destroy_terrain(&mut self, entity_id: EntityId) -> StateChanges {
+        let mut changes = StateChanges::new();
+        let Some(terrain) = self.world.get_entity_mut(entity_id) else {
+            return changes;
+        };
+
+        if terrain.collapse() {
+            tracing::debug!(entity_id, pos = ?terrain.pos(), "terrain destroyed");
+            changes.push(StateChange::TerrainDestroyed {
+                entity_id,
+                pos: terrain.pos(),
+            });
+        }
+        changes
+    }
+
+    /// Takes a dead entity out of play, leaving whatever it carried where it fell.
+    /// The player is kept in the world so the game can still be shown after death.
+    fn kill_entity(&mut self, entity_id: EntityId) -> StateChanges {
+        let mut changes = StateChanges::new();
+        self.turn_manager.remove_entity(entity_id);
+
+        if entity_id != self.world.player_id {
+            let pos = self.world.get_entity(entity_id).and_then(|e| e.pos());
+            let carried: Vec<EntityId> = self
+                .world
+                .get_inventory_items(entity_id)
+                .iter()
+                .map(|item| item.id)
+                .collect();
+
+            for item_id in carried {
+                if let Some(item) = self.world.get_entity_mut(item_id) {
+                    item.set_pos(pos);
+                }
+                if let Some(pos) = pos {
+                    changes.push(StateChange::ItemDropped {
+                        entity_id,
+                        item_id,
+                        pos,
+                    });
                 }
             }
-            GameEvent::SkipTurn => {}
+            self.world.remove_entity(entity_id);
         }
+
+        tracing::debug!(entity_id, "entity died");
+        changes.push(StateChange::EntityDied { entity_id });
+        changes
     }
 
     pub fn get_current_entity(&self) -> Option<EntityId> {
         self.turn_manager.current_entity()
     }
 
-    fn progress_turns(&mut self) -> () {
-        if let Some(entity_id) = self.turn_manager.next_turn() {
-            if let Some(next_entity) = self.world.get_entity_by_id(&entity_id) {
-                let is_player_turn = next_entity.id == self.world.player_id;
-                if is_player_turn {
-                    self.process_state = ProcessState::WaitingForPlayer;
-                } else {
-                    self.process_state = ProcessState::ProcessingTurns;
+    /// Moves on to the next entity with enough energy to act. Every turn starts with
+    /// regeneration and status effect ticks, and stunned entities lose their turn.
+    fn progress_turns(&mut self) -> StateChanges {
+        let mut changes = StateChanges::new();
+
+        while let Some(entity_id) = self
+            .turn_manager
+            .next_turn(|id| self.world.get_entity(id).map_or(0, |e| e.effective_speed()))
+        {
+            let turn = self.turn_manager.turn_number();
+            let _span = tracing::debug_span!("turn", turn, entity_id).entered();
+            let mut stunned = false;
+            if let Some(next_entity) = self.world.get_entity_by_id_mut(&entity_id) {
+                if let (Some(status), Some(stats)) =
+                    (next_entity.status.as_mut(), next_entity.stats.as_ref())
+                {
+                    status.regenerate(stats);
                 }
+                // Checked before ticking so a stun lasting one turn costs a turn
+                stunned = next_entity.is_stunned();
+            }
+            changes.extend(self.tick_effects(entity_id));
+
+            let Some(next_entity) = self.world.get_entity_by_id(&entity_id) else {
+                tracing::debug!("died to its effects before acting");
+                continue;
+            };
+            if stunned {
+                tracing::debug!("stunned, skipping turn");
+                self.turn_manager.spend_energy(entity_id, ACTION_ENERGY);
+                changes.push(StateChange::TurnSkipped { entity_id });
+                continue;
+            }
+
+            let is_player_turn = next_entity.id == self.world.player_id;
+            if is_player_turn {
+                self.process_state = ProcessState::WaitingForPlayer;
+            } else {
+                self.process_state = ProcessState::ProcessingTurns;
             }
+            changes.push(StateChange::TurnStarted { entity_id });
+            break;
         }
+        changes
     }
 
-    pub fn process_player_action(&mut self, event: GameEvent) -> () {
-        self.handle_event(event);
-        self.progress_turns();
+    /// Applies one turn of every status effect on an entity and removes the ones that ran out.
+    fn tick_effects(&mut self, entity_id: EntityId) -> StateChanges {
+        let mut changes = StateChanges::new();
+        let Some(entity) = self.world.get_entity_mut(entity_id) else {
+            return changes;
+        };
+
+        let mut damage = vec![];
+        for effect in entity.effects.iter_mut() {
+            if let (EffectKind::Regeneration { health_per_turn }, Some(status)) =
+                (&effect.kind, entity.status.as_mut())
+            {
+                let amount = status.health.restore(*health_per_turn);
+                if amount > 0 {
+                    changes.push(StateChange::EntityHealed { entity_id, amount });
+                }
+            }
+            if let Some((damage_type, amount)) = effect.damage_per_turn() {
+                damage.push((effect.source, damage_type, amount));
+            }
+            effect.remaining_turns = effect.remaining_turns.saturating_sub(1);
+            changes.push(StateChange::EffectTicked {
+                entity_id,
+                effect: effect.kind.clone(),
+                remaining_turns: effect.remaining_turns,
+            });
+        }
+
+        let (expired, active) = entity
+            .effects
+            .drain(..)
+            .partition(|effect| effect.remaining_turns == 0);
+        entity.effects = active;
+        for effect in expired {
+            changes.push(StateChange::EffectExpired {
+                entity_id,
+                effect: effect.kind,
+            });
+        }
+
+        for (source, damage_type, amount) in damage {
+            let dealt = self.world.get_entity(entity_id).map(|entity| DealtDamage {
+                amount: combat::mitigate(entity, &damage_type, amount),
+                damage_type,
+            });
+            changes.extend(self.damage_entity(
+                entity_id,
+                source,
+                DamageCause::Effect,
+                dealt.into_iter().collect(),
+            ));
+        }
+        changes
+    }
+
+    pub(crate) fn apply_effect(
+        &mut self,
+        entity_id: EntityId,
+        effect: StatusEffect,
+    ) -> StateChanges {
+        let mut changes = StateChanges::new();
+        if let Some(entity) = self.world.get_entity_mut(entity_id) {
+            entity.add_effect(effect.clone());
+            changes.push(StateChange::EffectApplied { entity_id, effect });
+        }
+        changes
+    }
+
+    /// Runs turns until it is the player's turn. Call once when a game starts.
+    pub fn start(&mut self) -> StateChanges {
+        // The player needs to see their surroundings before anyone acts.
+        self.world.update_visibility();
+        self.run_until_player_turn()
+    }
+
+    /// Carries out the player's action, then lets everyone else take their
+    /// turns until it is the player's turn again.
+    pub fn process_player_action(&mut self, event: GameEvent) -> EngineResult<StateChanges> {
+        let action = event.into_action();
+        let player_id = self.world.player_id;
+        // An action without an actor is taken by whoever's turn it is, so it
+        // must be the player's turn for it to count as the player's action
+        if action.actor().unwrap_or(player_id) != player_id {
+            return Err(EngineError::not_allowed(
+                "only the player's actions can be processed",
+            ));
+        }
+        let current = self.turn_manager.current_entity();
+        if current != Some(player_id) {
+            return Err(EngineError::NotEntitysTurn {
+                entity_id: player_id,
+                current,
+            });
+        }
+
+        let mut changes = self.perform_action(action.as_ref())?;
+        changes.extend(self.run_until_player_turn());
+        Ok(changes)
+    }
+
+    fn run_until_player_turn(&mut self) -> StateChanges {
+        let mut changes = self
+            .logged(|state| Ok(state.progress_turns()))
+            .unwrap_or_default();
+        for _ in 0..MAX_TURNS_BETWEEN_ACTIONS {
+            let player_alive = self
+                .world
+                .get_entity(self.world.player_id)
+                .is_some_and(|player| !player.is_dead());
+            let ai_turn = matches!(self.process_state, ProcessState::ProcessingTurns)
+                && self.turn_manager.current_entity().is_some();
+            if !player_alive || !ai_turn {
+                break;
+            }
+            changes.extend(self.process_ai_turn());
+        }
+        changes
     }
 
-    fn process_ai_turn(&mut self) -> () {
+    fn process_ai_turn(&mut self) -> StateChanges {
+        let mut changes = StateChanges::new();
         if let Some(entity_id) = self.turn_manager.current_entity() {
-            if let Some(entity) = self.world.get_entity_by_id(&entity_id) {
-                if let Some(ai) = entity.ai() {
-                    self.handle_event(ai.get_action(entity_id, &self.world));
-                } else {
-                    self.handle_event(GameEvent::SkipTurn);
+            let _span = tracing::debug_span!("ai_turn", entity_id).entered();
+            let action = self
+                .world
+                .get_entity_by_id(&entity_id)
+                .and_then(|entity| entity.ai())
+                .map(|ai| ai.get_action(entity_id, &self.world, &mut self.rng));
+
+            // An AI that can't do what it wanted just waits instead
+            let result = match action {
+                Some(action) => self.handle_event(action),
+                None => self.handle_event(GameEvent::SkipTurn),
+            }
+            .or_else(|_| self.handle_event(GameEvent::SkipTurn));
+            match result {
+                Ok(turn_changes) => changes.extend(turn_changes),
+                // Still use up the turn so the entity doesn't get it straight back
+                Err(error) => {
+                    tracing::warn!(%error, "could not even skip the turn");
+                    self.turn_manager.spend_energy(entity_id, ACTION_ENERGY);
                 }
             }
         }
-        self.progress_turns();
+        changes.extend(
+            self.logged(|state| Ok(state.progress_turns()))
+                .unwrap_or_default(),
+        );
+        changes
     }
 }
+
+/// An event bus with the message log already subscribed to it.
+pub(crate) fn event_bus_with(messages: &SharedMessageLog) -> EventBus {
+    let mut events = EventBus::new();
+    events.subscribe(messages.clone());
+    events
+}
@@ -0,0 +1,416 @@
+use std::{
+    collections::{BTreeMap, HashMap, VecDeque},
+    sync::{Arc, Mutex, MutexGuard, PoisonError},
+};
+
+use serde::{Deserialize, Serialize};
+use specta::Type;
+use ts_rs::TS;
+
+use crate::{
+    application::{
+        event_bus::{EventContext, Subscriber},
+        events::{DamageCause, DomainEvent, StateChange},
+    },
+    core::types::TurnNumber,
+    domain::entity::{
+        AttackOutcome, DamageType, EffectKind, EntityId, EntityKind, ItemKind, SpeciesKind,
+        SpellKind,
+    },
+};
+
+/// How many messages the log keeps before dropping the oldest.
+pub const MESSAGE_LOG_CAPACITY: usize = 200;
+
+/// The English message templates bundled with the engine.
+const ENGLISH_TEMPLATES: &str = include_str!("../../resources/messages/en.json");
+
+#[derive(PartialEq, Eq, Clone, Copy, Debug, Serialize, Deserialize, TS, Type)]
+#[ts(export)]
+pub enum Severity {
+    Info,
+    Good,
+    Warning,
+    Danger,
+}
+
+#[derive(PartialEq, Clone, Debug, Serialize, Deserialize, TS, Type)]
+#[ts(export)]
+pub struct Message {
+    pub turn: TurnNumber,
+    pub severity: Severity,
+    /// The template the message was built from.
+    pub key: String,
+    /// The values filled into the template's `{placeholders}`.
+    pub args: BTreeMap<String, String>,
+    /// The message rendered in the log's language.
+    pub text: String,
+}
+
+/// Message text by key, with `{name}` placeholders for arguments.
+#[derive(Debug, Clone)]
+pub struct MessageTemplates {
+    templates: HashMap<String, String>,
+}
+
+impl MessageTemplates {
+    /// Reads templates from a JSON object of keys to template strings.
+    pub fn from_json(json: &str) -> Result<Self, serde_json::Error> {
+        Ok(Self {
+            templates: serde_json::from_str(json)?,
+        })
+    }
+
+    pub fn english() -> Self {
+        Self::from_json(ENGLISH_TEMPLATES).expect("English message templates are invalid")
+    }
+
+    /// Looks up a template. Missing keys come back as the key itself so a gap
+    /// in a translation is visible rather than silent.
+    pub fn get<'a>(&'a self, key: &'a str) -> &'a str {
+        self.templates.get(key).map_or(key, String::as_str)
+    }
+
+    /// Fills a template with the given arguments and capitalises the result.
+    pub fn render(&self, key: &str, args: &BTreeMap<String, String>) -> String {
+        let text = args
+            .iter()
+            .fold(self.get(key).to_string(), |text, (name, value)| {
+                text.replace(&format!("{{{}}}", name), value)
+            });
+
+        let mut chars = text.chars();
+        match chars.next() {
+            Some(first) => first.to_uppercase().chain(chars).collect(),
+            None => text,
+        }
+    }
+}
+
+/// A bounded history of messages describing what happened in the game.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MessageLog {
+    messages: VecDeque<Message>,
+    capacity: usize,
+    /// Templates aren't saved, so a loaded log renders new messages in English.
+    #[serde(skip, default = "MessageTemplates::english")]
+    templates: MessageTemplates,
+}
+
+impl MessageLog {
+    pub fn new(templates: MessageTemplates, capacity: usize) -> Self {
+        Self {
+            messages: VecDeque::new(),
+            capacity,
+            templates,
+        }
+    }
+
+    pub fn messages(&self) -> &VecDeque<Message> {
+        &self.messages
+    }
+
+    /// The latest `count` messages, oldest first.
+    pub fn recent(&self, count: usize) -> Vec<Message> {
+        let skip = self.messages.len().saturating_sub(count);
+        self.messages.iter().skip(skip).cloned().collect()
+    }
+
+    pub fn clear(&mut self) {
+        self.messages.clear();
+    }
+
+    pub fn push(
+        &mut self,
+        turn: TurnNumber,
+        severity: Severity,
+        key: &str,
+        args: BTreeMap<String, String>,
+    ) {
+        let text = self.templates.render(key, &args);
+        self.messages.push_back(Message {
+            turn,
+            severity,
+            key: key.to_string(),
+            args,
+            text,
+        });
+        while self.messages.len() > self.capacity {
+            self.messages.pop_front();
+        }
+    }
+
+    fn record(&mut self, context: &EventContext, event: &DomainEvent) {
+        // The log only tells the player what they could have seen
+        if !event
+            .entities()
+            .into_iter()
+            .any(|id| context.player_sees(id))
+        {
+            return;
+        }
+        let player_id = context.world.player_id;
+        let name = |id: &EntityId| -> String {
+            self.templates
+                .get(name_key(context.kind_of(*id)))
+                .to_string()
+        };
+        // Templates have a `.player` variant for when the player is the one acting
+        let for_actor = |key: &str, actor: &EntityId| -> String {
+            let player_key = format!("{}.player", key);
+            if *actor == player_id && self.templates.templates.contains_key(&player_key) {
+                player_key
+            } else {
+                key.to_string()
+            }
+        };
+
+        let (key, severity, args) = match event {
+            DomainEvent::Attacked {
+                attacker_id,
+                defender_id,
+                roll,
+            } => {
+                let key = match roll.outcome {
+                    AttackOutcome::Hit => return,
+                    AttackOutcome::Miss => "attack.miss",
+                    AttackOutcome::Fumble => "attack.fumble",
+                    AttackOutcome::CriticalHit => "attack.critical",
+                };
+                (
+                    for_actor(key, attacker_id),
+                    danger_if(*defender_id == player_id, Severity::Info),
+                    args([
+                        ("attacker", name(attacker_id)),
+                        ("defender", name(defender_id)),
+                    ]),
+                )
+            }
+            DomainEvent::Damaged {
+                entity_id,
+                source,
+                cause,
+                amount,
+                damage_type,
+            } => {
+                let damage_type = self.templates.get(damage_type_key(damage_type)).to_string();
+                // Damage from lingering effects is reported as taken, not dealt
+                let key = match source {
+                    Some(source) if source != entity_id && *cause != DamageCause::Effect => {
+                        for_actor("damage.hit", source)
+                    }
+                    _ => for_actor("damage.taken", entity_id),
+                };
+                (
+                    key,
+                    danger_if(*entity_id == player_id, Severity::Info),
+                    args([
+                        ("source", source.as_ref().map(&name).unwrap_or_default()),
+                        ("target", name(entity_id)),
+                        ("amount", amount.to_string()),
+                        ("damage_type", damage_type),
+                    ]),
+                )
+            }
+            DomainEvent::Other(StateChange::EntityHealed { entity_id, amount }) => (
+                for_actor("healed", entity_id),
+                Severity::Good,
+                args([("entity", name(entity_id)), ("amount", amount.to_string())]),
+            ),
+            DomainEvent::Died { entity_id } => (
+                for_actor("died", entity_id),
+                danger_if(*entity_id == player_id, Severity::Good),
+                args([("entity", name(entity_id))]),
+            ),
+            DomainEvent::PickedUp { entity_id, item_id } => (
+                for_actor("item.picked_up", entity_id),
+                Severity::Info,
+                args([("entity", name(entity_id)), ("item", name(item_id))]),
+            ),
+            DomainEvent::Other(StateChange::ItemDropped {
+                entity_id, item_id, ..
+            }) => (
+                for_actor("item.dropped", entity_id),
+                Severity::Info,
+                args([("entity", name(entity_id)), ("item", name(item_id))]),
+            ),
+            DomainEvent::Other(StateChange::ItemEquipped { entity_id, item_id }) => (
+                for_actor("item.equipped", entity_id),
+                Severity::Info,
+                args([("entity", name(entity_id)), ("item", name(item_id))]),
+            ),
+            DomainEvent::Other(StateChange::ItemUnequipped { entity_id, item_id }) => (
+                for_actor("item.unequipped", entity_id),
+                Severity::Info,
+                args([("entity", name(entity_id)), ("item", name(item_id))]),
+            ),
+            DomainEvent::Other(StateChange::EffectApplied { entity_id, effect }) => (
+                for_actor("effect.applied", entity_id),
+                Severity::Warning,
+                args([
+                    ("entity", name(entity_id)),
+                    (
+                        "effect",
+                        self.templates.get(effect_key(&effect.kind)).to_string(),
+                    ),
+                ]),
+            ),
+            DomainEvent::Other(StateChange::EffectExpired { entity_id, effect }) => (
+                for_actor("effect.expired", entity_id),
+                Severity::Info,
+                args([
+                    ("entity", name(entity_id)),
+                    ("effect", self.templates.get(effect_key(effect)).to_string()),
+                ]),
+            ),
+            DomainEvent::Other(StateChange::TurnSkipped { entity_id }) => (
+                for_actor("turn.skipped", entity_id),
+                Severity::Warning,
+                args([("entity", name(entity_id))]),
+            ),
+            DomainEvent::Other(StateChange::SpellCast {
+                caster_id, spell, ..
+            }) => (
+                for_actor("spell.cast", caster_id),
+                Severity::Info,
+                args([
+                    ("caster", name(caster_id)),
+                    ("spell", self.templates.get(spell_key(spell)).to_string()),
+                ]),
+            ),
+            DomainEvent::Other(StateChange::DoorOpened { entity_id, door_id }) => (
+                for_actor("door.opened", entity_id),
+                Severity::Info,
+                args([("entity", name(entity_id)), ("door", name(door_id))]),
+            ),
+            DomainEvent::Other(StateChange::DoorClosed { entity_id, door_id }) => (
+                for_actor("door.closed", entity_id),
+                Severity::Info,
+                args([("entity", name(entity_id)), ("door", name(door_id))]),
+            ),
+            DomainEvent::Other(StateChange::DoorUnlocked { entity_id, door_id }) => (
+                for_actor("door.unlocked", entity_id),
+                Severity::Info,
+                args([("entity", name(entity_id)), ("door", name(door_id))]),
+            ),
+            DomainEvent::Other(StateChange::TerrainDestroyed { .. }) => (
+                "terrain.destroyed".to_string(),
+                Severity::Info,
+                BTreeMap::new(),
+            ),
+            DomainEvent::LevelChanged { from, to, .. } => (
+                if to > from {
+                    "level.descended".to_string()
+                } else {
+                    "level.ascended".to_string()
+                },
+                Severity::Info,
+                args([("depth", to.to_string())]),
+            ),
+            DomainEvent::Moved { .. } | DomainEvent::Other(_) => return,
+        };
+
+        self.push(context.turn, severity, &key, args);
+    }
+}
+
+impl Subscriber for MessageLog {
+    /// Adds a message for every event worth telling the player about.
+    fn notify(&mut self, context: &EventContext, event: &DomainEvent) {
+        self.record(context, event);
+    }
+}
+
+/// A message log that can be subscribed to the event bus and still be read
+/// by whoever holds a clone of it.
+#[derive(Debug, Clone, Default)]
+pub struct SharedMessageLog(Arc<Mutex<MessageLog>>);
+
+impl SharedMessageLog {
+    pub fn new(log: MessageLog) -> Self {
+        Self(Arc::new(Mutex::new(log)))
+    }
+
+    pub fn lock(&self) -> MutexGuard<'_, MessageLog> {
+        // The log is only ever appended to, so one left behind by a panic is still usable
+        self.0.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+}
+
+impl Subscriber for SharedMessageLog {
+    fn notify(&mut self, context: &EventContext, event: &DomainEvent) {
+        self.lock().notify(context, event);
+    }
+}
+
+impl Default for MessageLog {
+    fn default() -> Self {
+        Self::new(MessageTemplates::english(), MESSAGE_LOG_CAPACITY)
+    }
+}
+
+fn args<const N: usize>(args: [(&str, String); N]) -> BTreeMap<String, String> {
+    args.into_iter()
+        .map(|(name, value)| (name.to_string(), value))
+        .collect()
+}
+
+fn danger_if(condition: bool, otherwise: Severity) -> Severity {
+    if condition {
+        Severity::Danger
+    } else {
+        otherwise
+    }
+}
+
+fn name_key(kind: Option<&EntityKind>) -> &'static str {
+    match kind {
+        Some(EntityKind::Player { .. }) => "name.player",
+        Some(EntityKind::Npc { species }) => match species {
+            SpeciesKind::Human => "name.human",
+            SpeciesKind::Goblin => "name.goblin",
+        },
+        Some(EntityKind::Item { kind }) => match kind {
+            ItemKind::Weapon { .. } => "name.weapon",
+            ItemKind::Armor { .. } => "name.armor",
+            ItemKind::Key { .. } => "name.key",
+        },
+        Some(EntityKind::Wall { .. }) => "name.wall",
+        Some(EntityKind::Floor { .. }) => "name.floor",
+        Some(EntityKind::Door { .. }) => "name.door",
+        Some(EntityKind::Stairs { .. }) => "name.stairs",
+        None => "name.unknown",
+    }
+}
+
+fn damage_type_key(damage_type: &DamageType) -> &'static str {
+    match damage_type {
+        DamageType::Slice => "damage_type.slice",
+        DamageType::Pierce => "damage_type.pierce",
+        DamageType::Blunt => "damage_type.blunt",
+        DamageType::Fire => "damage_type.fire",
+        DamageType::Poison => "damage_type.poison",
+    }
+}
+
+fn effect_key(effect: &EffectKind) -> &'static str {
+    match effect {
+        EffectKind::Poison { .. } => "effect.poison",
+        EffectKind::Burning { .. } => "effect.burning",
+        EffectKind::Stun => "effect.stun",
+        EffectKind::Regeneration { .. } => "effect.regeneration",
+        EffectKind::Modifier { .. } => "effect.modifier",
+    }
+}
+
+fn spell_key(spell: &SpellKind) -> &'static str {
+    match spell {
+        SpellKind::Firebolt => "spell.firebolt",
+        SpellKind::Fireball => "spell.fireball",
+        SpellKind::FrostCone => "spell.frost_cone",
+        SpellKind::FlameLance => "spell.flame_lance",
+        SpellKind::Daze => "spell.daze",
+        SpellKind::Weaken => "spell.weaken",
+        SpellKind::Mend => "spell.mend",
+    }
+}
@@ -1,3 +1,11 @@
+pub mod actions;
+pub mod errors;
+pub mod event_bus;
 pub mod events;
 pub mod game_loop;
+pub mod messages;
+pub mod new_game;
+pub mod save;
+pub mod sync;
 pub mod turns;
+pub mod view;
@@ -0,0 +1,252 @@
+use rand::Rng;
+use serde::{Deserialize, Serialize};
+use specta::Type;
+use ts_rs::TS;
+
+use crate::{
+    application::{
+        errors::{EngineError, EngineResult},
+        game_loop::GameState,
+    },
+    core::{
+        dice::{Dice, DieRoll},
+        rng::Seed,
+    },
+    domain::entity::{
+        CoreAttributes, EntityBuilder, EntityId, EntityKind, Equipment, Inventory, ItemKind, Pool,
+        SpeciesKind, SpellKind,
+    },
+    prefabs,
+};
+
+/// Points there are to spend on attributes when buying them. Every point
+/// buys one point of an attribute.
+pub const POINT_BUY_BUDGET: u64 = 50;
+/// Lowest an attribute can start out at.
+pub const MIN_ATTRIBUTE: u64 = 1;
+/// Highest an attribute can start out at.
+pub const MAX_ATTRIBUTE: u64 = 18;
+
+/// How the player's attributes are decided.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, TS, Type)]
+#[serde(tag = "type")]
+#[ts(export)]
+pub enum AttributeChoice {
+    /// Attributes bought with up to [`POINT_BUY_BUDGET`] points.
+    PointBuy { attributes: CoreAttributes },
+    /// Every attribute is rolled as 4d6, keeping the highest three, and
+    /// shifted by how far the species' template strays from 10 in it.
+    Rolled,
+}
+
+/// What the player sets out with.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, TS, Type)]
+#[ts(export)]
+pub enum StartingKit {
+    /// A dagger and leather armor.
+    Fighter,
+    /// A shortbow.
+    Archer,
+    /// A few spells and nothing else.
+    Mage,
+}
+
+impl StartingKit {
+    pub const ALL: [StartingKit; 3] =
+        [StartingKit::Fighter, StartingKit::Archer, StartingKit::Mage];
+
+    /// The items in the kit. They start out carried and equipped.
+    fn items(&self) -> Vec<EntityBuilder> {
+        match self {
+            StartingKit::Fighter => vec![
+                prefabs::items::create_dagger(None),
+                prefabs::items::create_leather_armor(None),
+            ],
+            StartingKit::Archer => vec![prefabs::items::create_shortbow(None)],
+            StartingKit::Mage => Vec::new(),
+        }
+    }
+
+    fn spells(&self) -> Vec<SpellKind> {
+        match self {
+            StartingKit::Fighter | StartingKit::Archer => Vec::new(),
+            StartingKit::Mage => vec![SpellKind::Firebolt, SpellKind::Daze, SpellKind::Mend],
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, TS, Type)]
+#[ts(export)]
+pub enum Difficulty {
+    Easy,
+    Normal,
+    Hard,
+}
+
+impl Difficulty {
+    pub const ALL: [Difficulty; 3] = [Difficulty::Easy, Difficulty::Normal, Difficulty::Hard];
+
+    /// Percentage of the usual health and stamina the player starts with.
+    pub fn pool_percent(&self) -> u64 {
+        match self {
+            Difficulty::Easy => 150,
+            Difficulty::Normal => 100,
+            Difficulty::Hard => 75,
+        }
+    }
+}
+
+/// Everything chosen when starting a new game.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, TS, Type)]
+#[ts(export)]
+pub struct NewGameConfig {
+    /// The seed to start from, or `None` to pick one at random.
+    pub seed: Option<Seed>,
+    pub species: SpeciesKind,
+    pub attributes: AttributeChoice,
+    pub kit: StartingKit,
+    pub difficulty: Difficulty,
+}
+
+impl Default for NewGameConfig {
+    fn default() -> Self {
+        Self {
+            seed: None,
+            species: SpeciesKind::Human,
+            attributes: AttributeChoice::PointBuy {
+                attributes: CoreAttributes::default(),
+            },
+            kit: StartingKit::Mage,
+            difficulty: Difficulty::Normal,
+        }
+    }
+}
+
+impl NewGameConfig {
+    /// Checks that the choices make for a valid character.
+    pub fn validate(&self) -> EngineResult<()> {
+        let AttributeChoice::PointBuy { attributes } = &self.attributes else {
+            return Ok(());
+        };
+
+        let values = [
+            ("strength", attributes.strength),
+            ("speed", attributes.speed),
+            ("durability", attributes.durability),
+            ("fortitude", attributes.fortitude),
+            ("magic", attributes.magic),
+        ];
+        if let Some((name, _)) = values
+            .iter()
+            .find(|(_, value)| !(MIN_ATTRIBUTE..=MAX_ATTRIBUTE).contains(value))
+        {
+            return Err(EngineError::invalid_config(format!(
+                "{} must be between {} and {}",
+                name, MIN_ATTRIBUTE, MAX_ATTRIBUTE
+            )));
+        }
+
+        let spent: u64 = values.iter().map(|(_, value)| value).sum();
+        if spent > POINT_BUY_BUDGET {
+            return Err(EngineError::invalid_config(format!(
+                "{} attribute points were spent, but only {} are available",
+                spent, POINT_BUY_BUDGET
+            )));
+        }
+        Ok(())
+    }
+
+    /// Puts the player character together from their species' template.
+    /// Rolled attributes and the species' share of the pools are drawn from `rng`.
+    pub fn create_player<R: Rng + ?Sized>(&self, rng: &mut R) -> EntityBuilder {
+        let template = self.species.race().template();
+        let stats = match &self.attributes {
+            AttributeChoice::PointBuy { attributes } => attributes.clone(),
+            AttributeChoice::Rolled => {
+                let roll = DieRoll::new(4, Dice::D6, 0).keep_highest(3);
+                let usual = CoreAttributes::default();
+                let mut attribute = |species: u64, usual: u64| {
+                    let shift = species as i64 - usual as i64;
+                    (roll.roll_with(rng) + shift).clamp(MIN_ATTRIBUTE as i64, MAX_ATTRIBUTE as i64)
+                        as u64
+                };
+                CoreAttributes::new(
+                    attribute(template.stats.strength, usual.strength),
+                    attribute(template.stats.speed, usual.speed),
+                    attribute(template.stats.durability, usual.durability),
+                    attribute(template.stats.fortitude, usual.fortitude),
+                    attribute(template.stats.magic, usual.magic),
+                )
+            }
+        };
+
+        let mut status = template.roll_status(&stats, rng);
+        let percent = self.difficulty.pool_percent();
+        status.health = Pool::new((status.health.max * percent / 100).max(1));
+        status.stamina = Pool::new(status.stamina.max * percent / 100);
+
+        EntityBuilder::player(self.species.clone())
+            .status(status)
+            .stats(stats)
+            .inventory(Inventory::new())
+            .equipment(Equipment::new())
+            .spells(self.kit.spells())
+    }
+
+    /// Hands the player the items from their kit, already equipped.
+    pub fn give_kit(&self, state: &mut GameState, player_id: EntityId) -> EngineResult<()> {
+        for item in self.kit.items() {
+            let item_id = state.spawn(item);
+            let kind = match state.get_entity(item_id)?.kind() {
+                EntityKind::Item { kind } => kind.clone(),
+                _ => continue,
+            };
+
+            let player = state.get_entity_mut(player_id)?;
+            if let Some(inventory) = player.inventory_mut() {
+                inventory.add(item_id);
+            }
+            if let Some(equipment) = player.equipment_mut() {
+                match kind {
+                    ItemKind::Weapon { two_handed, .. } => {
+                        equipment.equip_weapon(item_id, two_handed, None);
+                    }
+                    ItemKind::Armor { .. } => {
+                        equipment.equip_armor(item_id);
+                    }
+                    ItemKind::Key { .. } => {}
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+/// The choices there are when creating a character, for building a character
+/// creation screen.
+#[derive(Debug, Clone, Serialize, TS, Type)]
+#[ts(export)]
+pub struct CharacterOptions {
+    pub species: Vec<SpeciesKind>,
+    pub kits: Vec<StartingKit>,
+    pub difficulties: Vec<Difficulty>,
+    pub point_buy_budget: u64,
+    pub min_attribute: u64,
+    pub max_attribute: u64,
+    /// A valid config to start the choices from.
+    pub defaults: NewGameConfig,
+}
+
+impl Default for CharacterOptions {
+    fn default() -> Self {
+        Self {
+            species: vec![SpeciesKind::Human, SpeciesKind::Goblin],
+            kits: StartingKit::ALL.to_vec(),
+            difficulties: Difficulty::ALL.to_vec(),
+            point_buy_budget: POINT_BUY_BUDGET,
+            min_attribute: MIN_ATTRIBUTE,
+            max_attribute: MAX_ATTRIBUTE,
+            defaults: NewGameConfig::default(),
+        }
+    }
+}
@@ -0,0 +1,67 @@
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    application::{
+        errors::{EngineError, EngineResult},
+        game_loop::{event_bus_with, GameState, ProcessState},
+        messages::{MessageLog, SharedMessageLog},
+        turns::TurnManager,
+    },
+    core::rng::{GameRng, Seed},
+    domain::world::World,
+};
+
+/// Raised whenever saves change in a way older ones can't be read with.
+pub const SAVE_VERSION: u32 = 1;
+
+/// Everything needed to pick a game back up where it was left.
+#[derive(Serialize, Deserialize)]
+struct SaveGame {
+    version: u32,
+    seed: Seed,
+    rng: GameRng,
+    world: World,
+    turn_manager: TurnManager,
+    process_state: ProcessState,
+    messages: MessageLog,
+}
+
+impl GameState {
+    /// Writes the game out as JSON. Event bus subscribers other than the
+    /// message log aren't part of it.
+    pub fn save(&self) -> EngineResult<String> {
+        let save = SaveGame {
+            version: SAVE_VERSION,
+            seed: self.seed,
+            rng: self.rng.clone(),
+            world: self.world.clone(),
+            turn_manager: self.turn_manager.clone(),
+            process_state: self.process_state,
+            messages: self.messages.lock().clone(),
+        };
+        serde_json::to_string(&save).map_err(EngineError::save_failed)
+    }
+
+    /// Reads back a game written by [`GameState::save`]. The message log is the
+    /// only event bus subscriber it starts out with.
+    pub fn load(json: &str) -> EngineResult<GameState> {
+        let save: SaveGame = serde_json::from_str(json).map_err(EngineError::invalid_save)?;
+        if save.version != SAVE_VERSION {
+            return Err(EngineError::invalid_save(format!(
+                "the save is from version {}, but only version {} can be read",
+                save.version, SAVE_VERSION
+            )));
+        }
+
+        let messages = SharedMessageLog::new(save.messages);
+        Ok(GameState {
+            world: save.world,
+            turn_manager: save.turn_manager,
+            process_state: save.process_state,
+            events: event_bus_with(&messages),
+            messages,
+            seed: save.seed,
+            rng: save.rng,
+        })
+    }
+}
@@ -0,0 +1,118 @@
+use std::collections::HashMap;
+
+use serde::Serialize;
+use specta::Type;
+use ts_rs::TS;
+
+use crate::{
+    application::view::PlayerView,
+    domain::{
+        dungeon::Depth,
+        entity::{Entity, EntityId},
+        world::World,
+    },
+};
+
+/// Counts the updates sent to a client. Every diff is numbered one higher than
+/// the update before it, so a client that sees a number skipped knows it has
+/// missed something and should ask for a snapshot.
+pub type Sequence = u64;
+
+/// What happened to a single entity since the last update.
+#[derive(Debug, Clone, Serialize, TS, Type)]
+#[ts(export)]
+#[serde(tag = "type")]
+pub enum EntityDiff {
+    Added { entity: Entity },
+    Changed { entity: Entity },
+    Removed { entity_id: EntityId },
+}
+
+/// What changed in the player's view since the previous update.
+#[derive(Debug, Clone, Serialize, TS, Type)]
+#[ts(export)]
+pub struct WorldDiff {
+    pub sequence: Sequence,
+    pub depth: Depth,
+    pub entities: Vec<EntityDiff>,
+}
+
+/// The player's whole view, for a client that is starting out or has fallen behind.
+#[derive(Debug, Clone, Serialize, TS, Type)]
+#[ts(export)]
+pub struct WorldSnapshot {
+    /// The next diff will be numbered one higher than this.
+    pub sequence: Sequence,
+    pub view: PlayerView,
+}
+
+/// Remembers what a client was last sent, so later updates only need to carry
+/// what changed.
+#[derive(Debug, Clone, Default)]
+pub struct WorldSync {
+    sequence: Sequence,
+    last_sent: HashMap<EntityId, Entity>,
+}
+
+impl WorldSync {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn sequence(&self) -> Sequence {
+        self.sequence
+    }
+
+    /// Forgets everything the client was sent, for when the game it follows is
+    /// replaced by another. Numbering carries on, so updates about the old game
+    /// can't be mistaken for new ones.
+    pub fn forget(&mut self) {
+        self.last_sent.clear();
+    }
+
+    /// Captures the player's whole view and makes it the baseline for later diffs.
+    pub fn snapshot(&mut self, world: &World) -> WorldSnapshot {
+        let view = PlayerView::new(world);
+        self.last_sent = view.entities.clone();
+        WorldSnapshot {
+            sequence: self.sequence,
+            view,
+        }
+    }
+
+    /// Works out what changed since the last update. Returns `None`, without
+    /// using up a sequence number, if nothing did.
+    pub fn diff(&mut self, world: &World) -> Option<WorldDiff> {
+        let view = PlayerView::new(world);
+        let mut entities: Vec<EntityDiff> = self
+            .last_sent
+            .keys()
+            .filter(|id| !view.entities.contains_key(id))
+            .map(|&entity_id| EntityDiff::Removed { entity_id })
+            .collect();
+
+        for (id, entity) in &view.entities {
+            match self.last_sent.get(id) {
+                None => entities.push(EntityDiff::Added {
+                    entity: entity.clone(),
+                }),
+                Some(sent) if sent != entity => entities.push(EntityDiff::Changed {
+                    entity: entity.clone(),
+                }),
+                Some(_) => {}
+            }
+        }
+
+        if entities.is_empty() {
+            return None;
+        }
+
+        self.last_sent = view.entities;
+        self.sequence += 1;
+        Some(WorldDiff {
+            sequence: self.sequence,
+            depth: view.depth,
+            entities,
+        })
+    }
+}
@@ -1,27 +1,44 @@
-use std::collections::VecDeque;
+use std::collections::{BTreeMap, HashMap, VecDeque};
 
-use crate::domain::entity::EntityId;
+use serde::{Deserialize, Serialize};
 
-/// Manages the order in which entities take turns.
+use crate::{
+    core::types::TurnNumber,
+    domain::{dungeon::Depth, entity::EntityId},
+};
+
+/// Energy an entity needs before it can act, and what an ordinary action costs.
+pub const ACTION_ENERGY: u64 = 100;
+
+/// Manages the order in which entities take turns. Entities gain energy by
+/// their speed and take a turn once they have enough of it, so faster
+/// entities act more often.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
 pub struct TurnManager {
     /// The ordered queue of entity IDs that will take turns.
     turn_queue: VecDeque<EntityId>,
     /// The entity currently taking its turn (if any).
     current: Option<EntityId>,
+    /// How many turns have been started so far.
+    turn_number: TurnNumber,
+    /// Energy saved up by each entity in the queue.
+    energy: HashMap<EntityId, u64>,
+    /// Entities waiting on levels the player isn't on, in their turn order,
+    /// with the energy they had saved up when they were left.
+    #[serde(default)]
+    parked: BTreeMap<Depth, Vec<(EntityId, u64)>>,
 }
 
 impl TurnManager {
     pub fn new() -> Self {
-        Self {
-            turn_queue: VecDeque::new(),
-            current: None,
-        }
+        Self::default()
     }
 
     /// Initialize the queue with given IDs, ensuring the player is first.
     /// For simplicity, we assume `player_id` must always be the first entity.
     pub fn initialize(&mut self, player_id: EntityId, other_ids: &[EntityId]) {
         self.turn_queue.clear();
+        self.energy.clear();
         // Put the player first
         self.turn_queue.push_back(player_id);
         // Then add any other entity IDs
@@ -45,35 +62,92 @@ impl TurnManager {
     /// If it is the current entity, `current` is cleared.
     pub fn remove_entity(&mut self, entity_id: EntityId) {
         self.turn_queue.retain(|&id| id != entity_id);
+        self.energy.remove(&entity_id);
         if self.current == Some(entity_id) {
             self.current = None;
         }
     }
 
-    /// Move to the next entity in the queue, cycling back to the front if needed.
-    /// Returns the new current entity, if the queue isn't empty.
-    pub fn next_turn(&mut self) -> Option<EntityId> {
+    /// Take entities out of the queue while their level is left behind,
+    /// keeping their turn order and energy for when it is visited again.
+    pub fn park(&mut self, depth: Depth, entity_ids: &[EntityId]) {
+        let parked = self.parked.entry(depth).or_default();
+        for &id in self.turn_queue.iter().filter(|id| entity_ids.contains(id)) {
+            parked.push((id, self.energy.remove(&id).unwrap_or(0)));
+        }
+        self.turn_queue.retain(|id| !entity_ids.contains(id));
+        if self.current.is_some_and(|id| entity_ids.contains(&id)) {
+            self.current = None;
+        }
+    }
+
+    /// Put the entities parked on a level back at the end of the queue, in the
+    /// order they had and with the energy they had saved up.
+    pub fn unpark(&mut self, depth: Depth) {
+        for (id, energy) in self.parked.remove(&depth).unwrap_or_default() {
+            if !self.turn_queue.contains(&id) {
+                self.turn_queue.push_back(id);
+                self.energy.insert(id, energy);
+            }
+        }
+    }
+
+    /// Move to the first entity in the queue with enough energy to act, giving
+    /// everyone energy by their speed until someone has enough. The entity
+    /// that acts goes to the back of the queue, so entities with the same
+    /// energy take turns. Returns the new current entity, if the queue isn't empty.
+    pub fn next_turn(&mut self, speed_of: impl Fn(EntityId) -> u64) -> Option<EntityId> {
         if self.turn_queue.is_empty() {
             self.current = None;
             return None;
         }
 
-        // If there's no current, pick the front of the queue
-        if self.current.is_none() {
-            self.current = self.turn_queue.front().copied();
-            return self.current;
-        }
+        let index = loop {
+            let ready = self
+                .turn_queue
+                .iter()
+                .position(|id| self.energy(*id) >= ACTION_ENERGY);
+            if let Some(index) = ready {
+                break index;
+            }
+            for &id in &self.turn_queue {
+                // Even the slowest entity gets a turn eventually
+                *self.energy.entry(id).or_default() += speed_of(id).max(1);
+            }
+        };
 
-        // Otherwise, rotate forward by one.
-        let front = self.turn_queue.pop_front().unwrap();
-        self.turn_queue.push_back(front);
-        self.current = self.turn_queue.front().copied();
-        println!("It is now Entity #{}'s turn!", self.current.unwrap());
+        self.turn_number += 1;
+        let entity_id = self.turn_queue.remove(index).unwrap();
+        self.turn_queue.push_back(entity_id);
+        self.current = Some(entity_id);
+        tracing::debug!(
+            entity_id,
+            energy = self.energy(entity_id),
+            turn = self.turn_number,
+            "turn started"
+        );
         self.current
     }
 
+    /// Use up energy once an entity has acted.
+    pub fn spend_energy(&mut self, entity_id: EntityId, amount: u64) {
+        if let Some(energy) = self.energy.get_mut(&entity_id) {
+            *energy = energy.saturating_sub(amount);
+        }
+    }
+
+    /// Get how much energy an entity has saved up.
+    pub fn energy(&self, entity_id: EntityId) -> u64 {
+        self.energy.get(&entity_id).copied().unwrap_or(0)
+    }
+
     /// Get the current entity who is acting.
     pub fn current_entity(&self) -> Option<EntityId> {
         self.current
     }
+
+    /// Get the number of the turn currently in progress.
+    pub fn turn_number(&self) -> TurnNumber {
+        self.turn_number
+    }
 }
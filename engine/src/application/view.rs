@@ -0,0 +1,64 @@
+use std::collections::{HashMap, HashSet};
+
+use serde::Serialize;
+use specta::Type;
+use ts_rs::TS;
+
+use crate::domain::{
+    dungeon::Depth,
+    entity::{Entity, EntityId},
+    world::World,
+};
+
+/// The world as the player knows it: the player and whatever they carry in
+/// full, what is in sight right now, and the terrain they remember. Everything
+/// else, such as creatures out of sight or what goes on in an AI's head, is
+/// left out so the client can't show it.
+#[derive(Debug, Clone, Serialize, TS, Type)]
+#[ts(export)]
+pub struct PlayerView {
+    pub player_id: EntityId,
+    pub depth: Depth,
+    pub entities: HashMap<EntityId, Entity>,
+}
+
+impl PlayerView {
+    /// Looks at the world through the player's eyes. Terrain that is out of
+    /// sight is shown the way the player last saw it, so changes they didn't
+    /// witness stay hidden.
+    pub fn new(world: &World) -> Self {
+        let player_id = world.player_id;
+        let carried: HashSet<EntityId> = world
+            .get_inventory_items(player_id)
+            .into_iter()
+            .chain(world.get_equipped_items(player_id))
+            .map(|item| item.id)
+            .collect();
+
+        let entities = world
+            .entities
+            .values()
+            .filter_map(|entity| {
+                if entity.id == player_id || carried.contains(&entity.id) {
+                    Some(entity.clone())
+                } else if entity.visible {
+                    Some(entity.outward())
+                } else if entity.discovered && !entity.is_creature() && !entity.is_item() {
+                    world.remembered.get(&entity.id).map(|seen| Entity {
+                        visible: false,
+                        ..seen.clone()
+                    })
+                } else {
+                    None
+                }
+            })
+            .map(|entity| (entity.id, entity))
+            .collect();
+
+        PlayerView {
+            player_id,
+            depth: world.depth,
+            entities,
+        }
+    }
+}
@@ -0,0 +1,530 @@
+use std::{fmt::Display, str::FromStr};
+
+use rand::Rng;
+use serde::{Deserialize, Serialize};
+use specta::Type;
+use ts_rs::TS;
+
+/// Upper bound on how many times a single exploding die can be rerolled.
+const MAX_EXPLOSIONS: u64 = 100;
+/// Most dice a parsed expression can roll at once.
+pub const MAX_DICE: u64 = 1000;
+
+#[derive(PartialEq, Clone, Debug, Serialize, Deserialize, TS, Type)]
+#[ts(export)]
+pub enum Dice {
+    D4,
+    D6,
+    D8,
+    D10,
+    D12,
+    D20,
+    D100,
+    Flat(u64),
+}
+
+impl Dice {
+    pub fn sides(&self) -> u64 {
+        match self {
+            Dice::D4 => 4,
+            Dice::D6 => 6,
+            Dice::D8 => 8,
+            Dice::D10 => 10,
+            Dice::D12 => 12,
+            Dice::D20 => 20,
+            Dice::D100 => 100,
+            Dice::Flat(sides) => *sides,
+        }
+    }
+
+    /// Gets the standard die with the given number of sides. Only d4, d6, d8,
+    /// d10, d12, d20 and d100 exist, so any other number gives `None`.
+    pub fn from_sides(sides: u64) -> Option<Dice> {
+        match sides {
+            4 => Some(Dice::D4),
+            6 => Some(Dice::D6),
+            8 => Some(Dice::D8),
+            10 => Some(Dice::D10),
+            12 => Some(Dice::D12),
+            20 => Some(Dice::D20),
+            100 => Some(Dice::D100),
+            _ => None,
+        }
+    }
+}
+
+/// Rolling an expression twice and keeping the better or worse result.
+#[derive(PartialEq, Eq, Clone, Copy, Debug, Serialize, TS, Type)]
+#[ts(export)]
+pub enum RollMode {
+    Normal,
+    Advantage,
+    Disadvantage,
+}
+
+/// A dice expression such as `2d6+3`, `4d6kh3` or `1d6!`.
+#[derive(PartialEq, Clone, Debug, Serialize, Deserialize, TS, Type)]
+#[ts(export)]
+pub struct DieRoll {
+    pub count: u64,
+    pub dice: Dice,
+    pub modifier: i64,
+    /// Only the highest this many dice count towards the result.
+    pub keep_highest: Option<u64>,
+    /// A die that rolls its highest face is rolled again and added.
+    pub exploding: bool,
+}
+
+impl DieRoll {
+    pub fn new(count: u64, dice: Dice, modifier: i64) -> Self {
+        Self {
+            count,
+            dice,
+            modifier,
+            keep_highest: None,
+            exploding: false,
+        }
+    }
+
+    pub fn keep_highest(mut self, keep: u64) -> Self {
+        self.keep_highest = Some(keep);
+        self
+    }
+
+    pub fn exploding(mut self) -> Self {
+        self.exploding = true;
+        self
+    }
+
+    /// Rolls the expression with the thread-local RNG.
+    pub fn roll(&self) -> i64 {
+        self.roll_with(&mut rand::rng())
+    }
+
+    /// Rolls the expression with the given RNG.
+    pub fn roll_with<R: Rng + ?Sized>(&self, rng: &mut R) -> i64 {
+        if let Dice::Flat(value) = self.dice {
+            return with_modifier(value.saturating_mul(self.count), self.modifier);
+        }
+
+        let total = match self.keep_highest {
+            Some(keep) if keep < self.count => {
+                let mut rolls: Vec<u64> = (0..self.count).map(|_| self.roll_die(rng)).collect();
+                rolls.sort_unstable_by(|a, b| b.cmp(a));
+                rolls
+                    .iter()
+                    .take(keep as usize)
+                    .fold(0u64, |total, roll| total.saturating_add(*roll))
+            }
+            _ => (0..self.count).fold(0u64, |total, _| total.saturating_add(self.roll_die(rng))),
+        };
+        with_modifier(total, self.modifier)
+    }
+
+    /// Rolls the expression, rolling twice and keeping the higher result with
+    /// advantage or the lower result with disadvantage.
+    pub fn roll_with_mode<R: Rng + ?Sized>(&self, mode: RollMode, rng: &mut R) -> i64 {
+        match mode {
+            RollMode::Normal => self.roll_with(rng),
+            RollMode::Advantage => self.roll_with(rng).max(self.roll_with(rng)),
+            RollMode::Disadvantage => self.roll_with(rng).min(self.roll_with(rng)),
+        }
+    }
+
+    /// The lowest possible result.
+    pub fn min(&self) -> i64 {
+        match self.dice {
+            Dice::Flat(value) => with_modifier(value.saturating_mul(self.count), self.modifier),
+            _ => with_modifier(self.kept_dice(), self.modifier),
+        }
+    }
+
+    /// The highest possible result, or `None` if exploding dice make it unbounded.
+    pub fn max(&self) -> Option<i64> {
+        match self.dice {
+            Dice::Flat(value) => Some(with_modifier(
+                value.saturating_mul(self.count),
+                self.modifier,
+            )),
+            _ if self.exploding => None,
+            _ => Some(with_modifier(
+                self.kept_dice().saturating_mul(self.dice.sides()),
+                self.modifier,
+            )),
+        }
+    }
+
+    /// The average result over many rolls.
+    ///
+    /// Exact except when dice both explode and only the highest are kept. The
+    /// average of the kept dice is then scaled up by how much exploding raises
+    /// the average of a single die, which comes out somewhat low, since the
+    /// dice that explode are also the ones most likely to be kept.
+    pub fn expected_value(&self) -> f64 {
+        let sides = match self.dice {
+            Dice::Flat(value) => return value as f64 * self.count as f64 + self.modifier as f64,
+            ref dice => dice.sides(),
+        };
+
+        let dice_total = match self.keep_highest {
+            Some(keep) if keep < self.count => {
+                expected_highest(self.count, keep, sides) * self.explosion_factor(sides)
+            }
+            _ => self.count as f64 * (sides as f64 + 1.0) / 2.0 * self.explosion_factor(sides),
+        };
+        dice_total + self.modifier as f64
+    }
+
+    fn kept_dice(&self) -> u64 {
+        self.keep_highest
+            .map_or(self.count, |keep| keep.min(self.count))
+    }
+
+    /// How much exploding scales the average of a die.
+    fn explosion_factor(&self, sides: u64) -> f64 {
+        if self.exploding && sides > 1 {
+            sides as f64 / (sides as f64 - 1.0)
+        } else {
+            1.0
+        }
+    }
+
+    fn roll_die<R: Rng + ?Sized>(&self, rng: &mut R) -> u64 {
+        let sides = self.dice.sides();
+        let mut total = rng.random_range(1..=sides);
+        if self.exploding && sides > 1 {
+            let mut last = total;
+            let mut explosions = 0;
+            while last == sides && explosions < MAX_EXPLOSIONS {
+                last = rng.random_range(1..=sides);
+                total += last;
+                explosions += 1;
+            }
+        }
+        total
+    }
+}
+
+/// Expected sum of the `keep` highest of `count` dice with `sides` sides.
+///
+/// The j-th highest die is at least `x` exactly when at least j dice are, so
+/// summing those probabilities over every face gives the expected value.
+fn expected_highest(count: u64, keep: u64, sides: u64) -> f64 {
+    (1..=sides)
+        .map(|x| {
+            let p = (sides - x + 1) as f64 / sides as f64;
+            (1..=keep)
+                .map(|j| {
+                    (j..=count)
+                        .map(|m| {
+                            binomial(count, m)
+                                * p.powi(m as i32)
+                                * (1.0 - p).powi((count - m) as i32)
+                        })
+                        .sum::<f64>()
+                })
+                .sum::<f64>()
+        })
+        .sum()
+}
+
+/// Adds the modifier to a total of dice, saturating instead of overflowing.
+fn with_modifier(total: u64, modifier: i64) -> i64 {
+    i64::try_from(total)
+        .unwrap_or(i64::MAX)
+        .saturating_add(modifier)
+}
+
+fn binomial(n: u64, k: u64) -> f64 {
+    (0..k).fold(1.0, |acc, i| acc * (n - i) as f64 / (i + 1) as f64)
+}
+
+#[derive(PartialEq, Eq, Clone, Debug)]
+pub enum ParseDiceError {
+    Empty,
+    InvalidNumber(String),
+    /// Only d4, d6, d8, d10, d12, d20 and d100 (or d%) can be rolled.
+    UnsupportedDie(u64),
+    UnexpectedInput(String),
+    /// More than [`MAX_DICE`] dice, or a result too large to count.
+    TooLarge(String),
+}
+
+impl Display for ParseDiceError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ParseDiceError::Empty => write!(f, "empty dice expression"),
+            ParseDiceError::InvalidNumber(number) => write!(f, "invalid number '{}'", number),
+            ParseDiceError::UnsupportedDie(sides) => write!(
+                f,
+                "there is no d{} die, only d4, d6, d8, d10, d12, d20 and d100",
+                sides
+            ),
+            ParseDiceError::UnexpectedInput(rest) => write!(f, "unexpected input '{}'", rest),
+            ParseDiceError::TooLarge(expression) => {
+                write!(f, "dice expression '{}' is too large", expression)
+            }
+        }
+    }
+}
+
+impl std::error::Error for ParseDiceError {}
+
+impl FromStr for DieRoll {
+    type Err = ParseDiceError;
+
+    /// Parses expressions like `2d6+3`, `d20-1`, `d%`, `4d6kh3`, `1d6!` or a
+    /// flat number such as `5`.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let s: String = s.chars().filter(|c| !c.is_whitespace()).collect();
+        let s = s.to_lowercase();
+        if s.is_empty() {
+            return Err(ParseDiceError::Empty);
+        }
+
+        // Split off the trailing modifier, if any
+        let (expression, modifier) = match s.rfind(['+', '-']) {
+            Some(0) | None => (s.as_str(), 0),
+            Some(index) => {
+                let modifier = &s[index..];
+                let modifier = modifier
+                    .parse::<i64>()
+                    .map_err(|_| ParseDiceError::InvalidNumber(modifier.to_string()))?;
+                (&s[..index], modifier)
+            }
+        };
+
+        let Some((count, rest)) = expression.split_once('d') else {
+            let value = parse_number(expression)?;
+            if i64::try_from(value).is_err() {
+                return Err(ParseDiceError::TooLarge(s));
+            }
+            return Ok(DieRoll::new(1, Dice::Flat(value), modifier));
+        };
+
+        let count = if count.is_empty() {
+            1
+        } else {
+            parse_number(count)?
+        };
+        if count > MAX_DICE {
+            return Err(ParseDiceError::TooLarge(s));
+        }
+
+        let (rest, exploding) = match rest.strip_suffix('!') {
+            Some(rest) => (rest, true),
+            None => (rest, false),
+        };
+        let (sides, keep_highest) = match rest.split_once("kh") {
+            Some((sides, keep)) => (sides, Some(parse_number(keep)?)),
+            None => (rest, None),
+        };
+
+        let dice = if sides == "%" {
+            Dice::D100
+        } else {
+            let sides = parse_number(sides)?;
+            Dice::from_sides(sides).ok_or(ParseDiceError::UnsupportedDie(sides))?
+        };
+        let highest = count
+            .checked_mul(dice.sides())
+            .and_then(|highest| i64::try_from(highest).ok());
+        if highest.is_none() {
+            return Err(ParseDiceError::TooLarge(s));
+        }
+
+        Ok(DieRoll {
+            count,
+            dice,
+            modifier,
+            keep_highest,
+            exploding,
+        })
+    }
+}
+
+fn parse_number(s: &str) -> Result<u64, ParseDiceError> {
+    if s.is_empty() {
+        return Err(ParseDiceError::UnexpectedInput(s.to_string()));
+    }
+    s.parse()
+        .map_err(|_| ParseDiceError::InvalidNumber(s.to_string()))
+}
+
+impl Display for DieRoll {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self.dice {
+            Dice::Flat(value) => write!(f, "{}", value.saturating_mul(self.count))?,
+            ref dice => {
+                write!(f, "{}d{}", self.count, dice.sides())?;
+                if let Some(keep) = self.keep_highest {
+                    write!(f, "kh{}", keep)?;
+                }
+                if self.exploding {
+                    write!(f, "!")?;
+                }
+            }
+        }
+        match self.modifier {
+            0 => Ok(()),
+            modifier if modifier > 0 => write!(f, "+{}", modifier),
+            modifier => write!(f, "{}", modifier),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::rng;
+
+    fn parse(s: &str) -> Result<DieRoll, ParseDiceError> {
+        s.parse()
+    }
+
+    #[test]
+    fn parses_plain_dice_with_modifiers() {
+        assert_eq!(parse("2d6+3"), Ok(DieRoll::new(2, Dice::D6, 3)));
+        assert_eq!(parse("d20-1"), Ok(DieRoll::new(1, Dice::D20, -1)));
+        assert_eq!(parse(" 3 D 8 "), Ok(DieRoll::new(3, Dice::D8, 0)));
+        assert_eq!(parse("d%"), Ok(DieRoll::new(1, Dice::D100, 0)));
+        assert_eq!(parse("5"), Ok(DieRoll::new(1, Dice::Flat(5), 0)));
+    }
+
+    #[test]
+    fn parses_keep_highest_and_exploding() {
+        assert_eq!(
+            parse("4d6kh3"),
+            Ok(DieRoll::new(4, Dice::D6, 0).keep_highest(3))
+        );
+        assert_eq!(
+            parse("1d6!+2"),
+            Ok(DieRoll::new(1, Dice::D6, 2).exploding())
+        );
+        assert_eq!(
+            parse("4d6kh3!"),
+            Ok(DieRoll::new(4, Dice::D6, 0).keep_highest(3).exploding())
+        );
+    }
+
+    #[test]
+    fn display_round_trips() {
+        for expression in ["2d6+3", "1d20-1", "4d6kh3", "1d6!", "4d10kh2!+1", "7"] {
+            assert_eq!(parse(expression).unwrap().to_string(), expression);
+        }
+    }
+
+    #[test]
+    fn rejects_malformed_expressions() {
+        assert_eq!(parse(""), Err(ParseDiceError::Empty));
+        assert_eq!(parse("  "), Err(ParseDiceError::Empty));
+        assert_eq!(
+            parse("xd6"),
+            Err(ParseDiceError::InvalidNumber("x".to_string()))
+        );
+        assert_eq!(
+            parse("2d"),
+            Err(ParseDiceError::UnexpectedInput(String::new()))
+        );
+        assert_eq!(
+            parse("4d6kh"),
+            Err(ParseDiceError::UnexpectedInput(String::new()))
+        );
+        assert_eq!(
+            parse("2d6+x"),
+            Err(ParseDiceError::InvalidNumber("+x".to_string()))
+        );
+        assert_eq!(parse("2d3"), Err(ParseDiceError::UnsupportedDie(3)));
+        assert_eq!(parse("1d0"), Err(ParseDiceError::UnsupportedDie(0)));
+    }
+
+    #[test]
+    fn rejects_overflowing_expressions() {
+        assert!(matches!(
+            parse("99999999999999999999d6"),
+            Err(ParseDiceError::InvalidNumber(_))
+        ));
+        assert!(matches!(
+            parse("1d6+99999999999999999999"),
+            Err(ParseDiceError::InvalidNumber(_))
+        ));
+        assert!(matches!(
+            parse("9223372036854775807d6"),
+            Err(ParseDiceError::TooLarge(_))
+        ));
+        assert!(matches!(
+            parse("1537228672809129301d6"),
+            Err(ParseDiceError::TooLarge(_))
+        ));
+        assert!(matches!(
+            parse(&format!("{}d6", MAX_DICE + 1)),
+            Err(ParseDiceError::TooLarge(_))
+        ));
+        assert!(parse(&format!("{}d6kh3", MAX_DICE)).is_ok());
+        assert!(matches!(
+            parse("9223372036854775808"),
+            Err(ParseDiceError::TooLarge(_))
+        ));
+    }
+
+    #[test]
+    fn large_flat_values_saturate() {
+        let roll = DieRoll::new(u64::MAX, Dice::Flat(u64::MAX), 10);
+        assert_eq!(roll.min(), i64::MAX);
+        assert_eq!(roll.max(), Some(i64::MAX));
+        assert_eq!(roll.roll_with(&mut rng::seeded(0)), i64::MAX);
+        assert_eq!(roll.to_string(), format!("{}+10", u64::MAX));
+    }
+
+    #[test]
+    fn rolls_stay_within_bounds() {
+        let mut rng = rng::seeded(1);
+        for expression in ["1d4", "3d6+2", "2d20-5", "4d6kh3", "1d100"] {
+            let roll = parse(expression).unwrap();
+            let (min, max) = (roll.min(), roll.max().unwrap());
+            for _ in 0..1000 {
+                let result = roll.roll_with(&mut rng);
+                assert!(
+                    (min..=max).contains(&result),
+                    "{} rolled {}",
+                    expression,
+                    result
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn every_face_can_come_up() {
+        let mut rng = rng::seeded(2);
+        for dice in [Dice::D4, Dice::D6, Dice::D20] {
+            let roll = DieRoll::new(1, dice.clone(), 0);
+            let mut seen = vec![false; dice.sides() as usize];
+            for _ in 0..10_000 {
+                seen[roll.roll_with(&mut rng) as usize - 1] = true;
+            }
+            assert!(seen.iter().all(|&face| face), "{:?} missed a face", dice);
+        }
+    }
+
+    #[test]
+    fn exploding_dice_go_past_the_top_face() {
+        let mut rng = rng::seeded(3);
+        let roll = DieRoll::new(1, Dice::D4, 0).exploding();
+        assert_eq!(roll.max(), None);
+
+        let results: Vec<i64> = (0..1000).map(|_| roll.roll_with(&mut rng)).collect();
+        assert!(results.iter().all(|&result| result >= 1 && result != 4));
+        assert!(results.iter().any(|&result| result > 4));
+    }
+
+    #[test]
+    fn expected_values() {
+        assert_eq!(parse("2d6+3").unwrap().expected_value(), 10.0);
+        assert_eq!(parse("5").unwrap().expected_value(), 5.0);
+        let kept = parse("4d6kh3").unwrap().expected_value();
+        assert!((kept - 12.2446).abs() < 0.001, "4d6kh3 averaged {}", kept);
+        let exploding = parse("1d6!").unwrap().expected_value();
+        assert!((exploding - 4.2).abs() < 1e-9);
+    }
+}
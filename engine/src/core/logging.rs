@@ -0,0 +1,25 @@
+use std::path::Path;
+
+use tracing_appender::non_blocking::WorkerGuard;
+use tracing_subscriber::{fmt, layer::SubscriberExt, util::SubscriberInitExt, EnvFilter};
+
+/// Log filter used when `RUST_LOG` isn't set.
+pub const DEFAULT_LOG_FILTER: &str = "info,engine=debug";
+
+/// Logs to stdout and to a daily rolling `game.log` in `log_dir`, for any
+/// binary running the engine. Levels are set with `RUST_LOG`, e.g.
+/// `RUST_LOG=engine::domain::entity::ai=trace`. The returned guard flushes the
+/// log file when dropped, so keep it for as long as the program runs.
+pub fn init_tracing(log_dir: &Path) -> WorkerGuard {
+    let (file_writer, guard) =
+        tracing_appender::non_blocking(tracing_appender::rolling::daily(log_dir, "game.log"));
+    let filter =
+        EnvFilter::try_from_default_env().unwrap_or_else(|_| EnvFilter::new(DEFAULT_LOG_FILTER));
+
+    tracing_subscriber::registry()
+        .with(filter)
+        .with(fmt::layer())
+        .with(fmt::layer().with_ansi(false).with_writer(file_writer))
+        .init();
+    guard
+}
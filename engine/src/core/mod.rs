@@ -1 +1,5 @@
+pub mod dice;
+#[cfg(feature = "logging")]
+pub mod logging;
+pub mod rng;
 pub mod types;
@@ -0,0 +1,32 @@
+use rand::SeedableRng;
+use rand_chacha::ChaCha8Rng;
+
+/// What a game's randomness grows from. Games started from the same seed play
+/// out the same way when given the same actions. It is kept to 32 bits so it
+/// survives being passed through a JavaScript number.
+pub type Seed = u32;
+
+/// The stream the player character is rolled from. Keeping it apart from the
+/// game's own stream means character creation neither uses up nor repeats any
+/// of the numbers the game goes on to draw.
+const CHARACTER_STREAM: u64 = 1;
+
+/// The random number generator a game draws from. Its state is saved along
+/// with the game, so a loaded game carries on exactly where it left off.
+pub type GameRng = ChaCha8Rng;
+
+pub fn seeded(seed: Seed) -> GameRng {
+    GameRng::seed_from_u64(seed.into())
+}
+
+/// The generator for rolling up the player character of a game started from `seed`.
+pub fn character(seed: Seed) -> GameRng {
+    let mut rng = seeded(seed);
+    rng.set_stream(CHARACTER_STREAM);
+    rng
+}
+
+/// Picks a seed for a game that wasn't given one.
+pub fn random_seed() -> Seed {
+    rand::random()
+}
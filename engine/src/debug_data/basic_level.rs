@@ -1,43 +1,77 @@
 use crate::{
-    domain::entity::{types::EntityWithoutId, Entity},
+    domain::entity::{builder::EntityBuilder, StairDirection},
     prefabs,
 };
 
-pub fn get_level() -> Vec<EntityWithoutId> {
-    let left_wall: Vec<EntityWithoutId> = (-4..4)
+pub fn get_level() -> Vec<EntityBuilder> {
+    let left_wall: Vec<EntityBuilder> = (-4..4)
         .map(|y| {
             prefabs::dungeon_environment::create_stone_wall(Some(
                 crate::domain::world_position::WorldPosition { x: -4, y },
             ))
         })
         .collect();
-    let right_wall: Vec<EntityWithoutId> = (-4..5)
+    let right_wall: Vec<EntityBuilder> = (-4..5)
         .map(|y| {
             prefabs::dungeon_environment::create_stone_wall(Some(
                 crate::domain::world_position::WorldPosition { x: 8, y },
             ))
         })
         .collect();
-    let up_wall: Vec<EntityWithoutId> = (-4..8)
+    let up_wall: Vec<EntityBuilder> = (-4..8)
         .map(|x| {
             prefabs::dungeon_environment::create_stone_wall(Some(
                 crate::domain::world_position::WorldPosition { x, y: 4 },
             ))
         })
         .collect();
-    let down_wall: Vec<EntityWithoutId> = (-4..8)
+    let down_wall: Vec<EntityBuilder> = (-4..8)
         .map(|x| {
             prefabs::dungeon_environment::create_stone_wall(Some(
                 crate::domain::world_position::WorldPosition { x, y: -4 },
             ))
         })
         .collect();
-    let middle_wall: Vec<EntityWithoutId> = (-1..5)
+    let middle_wall: Vec<EntityBuilder> = (-1..5)
         .map(|y| {
             prefabs::dungeon_environment::create_stone_wall(Some(
                 crate::domain::world_position::WorldPosition { x: 3, y },
             ))
         })
         .collect();
-    [left_wall, right_wall, up_wall, down_wall, middle_wall].concat()
+    let middle_door = vec![
+        prefabs::dungeon_environment::create_stone_wall(Some(
+            crate::domain::world_position::WorldPosition { x: 3, y: -3 },
+        )),
+        prefabs::dungeon_environment::create_wooden_door(
+            Some(crate::domain::world_position::WorldPosition { x: 3, y: -2 }),
+            None,
+        ),
+    ];
+    // Floor everywhere inside the outer walls except the middle wall, whose
+    // doorway is walkable by itself
+    let floor: Vec<EntityBuilder> = (-3..8)
+        .filter(|x| *x != 3)
+        .flat_map(|x| (-3..4).map(move |y| (x, y)))
+        .map(|(x, y)| {
+            prefabs::dungeon_environment::create_stone_floor(Some(
+                crate::domain::world_position::WorldPosition { x, y },
+            ))
+        })
+        .collect();
+    let stairs = vec![prefabs::dungeon_environment::create_stone_stairs(
+        Some(crate::domain::world_position::WorldPosition { x: 6, y: 2 }),
+        StairDirection::Down,
+    )];
+    [
+        left_wall,
+        right_wall,
+        up_wall,
+        down_wall,
+        middle_wall,
+        middle_door,
+        floor,
+        stairs,
+    ]
+    .concat()
 }
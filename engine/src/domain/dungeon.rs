@@ -0,0 +1,91 @@
+use std::collections::BTreeMap;
+
+use serde::{Deserialize, Serialize};
+use specta::Type;
+use ts_rs::TS;
+
+use super::{
+    entity::{Entity, EntityId, EntityKind, StairDirection},
+    world::World,
+    world_position::WorldPosition,
+};
+
+/// How far down a level is. The first level is at depth 0.
+pub type Depth = u32;
+
+/// A level the player has left, kept exactly as it was, along with what the
+/// player remembers of it.
+#[derive(Debug, Clone, Serialize, Deserialize, TS, Type)]
+#[ts(export)]
+pub struct Level {
+    pub depth: Depth,
+    pub entities: BTreeMap<EntityId, Entity>,
+    /// Terrain on the level as the player last saw it.
+    #[serde(default)]
+    pub remembered: BTreeMap<EntityId, Entity>,
+}
+
+impl World {
+    /// Packs away everything on the current level except `travellers` and
+    /// whatever they carry, then unpacks the level at `depth` if it has been
+    /// visited before. Returns whether the level at `depth` was already known.
+    pub fn switch_level(&mut self, depth: Depth, travellers: &[EntityId]) -> bool {
+        let staying = self.take_entities(travellers);
+        let leaving = std::mem::replace(&mut self.entities, staying);
+
+        self.levels.insert(
+            self.depth,
+            Level {
+                depth: self.depth,
+                entities: leaving,
+                remembered: std::mem::take(&mut self.remembered),
+            },
+        );
+        self.depth = depth;
+
+        match self.levels.remove(&depth) {
+            Some(level) => {
+                self.entities.extend(level.entities);
+                self.remembered = level.remembered;
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Moves entities and whatever they carry from the current level onto a
+    /// level that has been left.
+    pub fn send_to_level(&mut self, depth: Depth, ids: &[EntityId]) {
+        let entities = self.take_entities(ids);
+        self.levels
+            .entry(depth)
+            .or_insert_with(|| Level {
+                depth,
+                entities: BTreeMap::new(),
+                remembered: BTreeMap::new(),
+            })
+            .entities
+            .extend(entities);
+    }
+
+    /// Finds the stairs going the given way on the current level.
+    pub fn get_stairs(&self, direction: StairDirection) -> Option<WorldPosition> {
+        self.entities
+            .values()
+            .find(|e| matches!(e.kind, EntityKind::Stairs { direction: d, .. } if d == direction))
+            .and_then(|e| e.pos())
+    }
+
+    /// Removes the given entities and everything in their inventories.
+    fn take_entities(&mut self, ids: &[EntityId]) -> BTreeMap<EntityId, Entity> {
+        let carried: Vec<EntityId> = ids
+            .iter()
+            .flat_map(|id| self.get_inventory_items(*id))
+            .map(|item| item.id)
+            .collect();
+        ids.iter()
+            .chain(carried.iter())
+            .filter_map(|id| self.entities.remove(id).map(|entity| (*id, entity)))
+            .collect()
+    }
+}
@@ -1,4 +1,5 @@
-use serde::Serialize;
+use rand::Rng;
+use serde::{Deserialize, Serialize};
 use specta::Type;
 use ts_rs::TS;
 
@@ -6,10 +7,13 @@ use super::EntityId;
 use crate::{
     application::events::GameEvent,
     core::types::Direction,
-    domain::{world::World, world_position::WorldPosition},
+    domain::{
+        world::{World, SIGHT_RADIUS},
+        world_position::WorldPosition,
+    },
 };
 
-#[derive(Debug, Clone, Serialize, TS, Type)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, TS, Type)]
 #[ts(export)]
 pub struct LastSeen {
     entity: EntityId,
@@ -17,13 +21,13 @@ pub struct LastSeen {
     on_turn: usize,
 }
 
-#[derive(Debug, Clone, Serialize, TS, Type)]
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize, TS, Type)]
 #[ts(export)]
 pub struct Memory {
     last_seen_positions: Vec<LastSeen>,
 }
 
-#[derive(Debug, Clone, Serialize, TS, Type)]
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize, TS, Type)]
 #[ts(export)]
 pub struct Ai {
     memory: Memory,
@@ -31,22 +35,49 @@ pub struct Ai {
 
 impl Ai {
     pub fn new() -> Self {
-        Ai {
-            memory: Memory {
-                last_seen_positions: vec![],
-            },
-        }
+        Self::default()
     }
 
-    pub fn get_action(&self, entity_id: EntityId, _world: &World) -> GameEvent {
-        // TODO actual logic
+    pub fn get_action<R: Rng + ?Sized>(
+        &self,
+        entity_id: EntityId,
+        world: &World,
+        rng: &mut R,
+    ) -> GameEvent {
+        if let Some(dir) = self.get_step_towards_player(entity_id, world) {
+            tracing::debug!(entity_id, ?dir, "chasing the player");
+            return GameEvent::MoveByDirection(entity_id, dir);
+        }
+
         let directions: [Direction; 4] = [
             Direction::North,
             Direction::East,
             Direction::South,
             Direction::West,
         ];
-        let random_direction = directions[rand::random_range(0..directions.len())];
+        let random_direction = directions[rng.random_range(0..directions.len())];
+        tracing::debug!(entity_id, dir = ?random_direction, "wandering");
         GameEvent::MoveByDirection(entity_id, random_direction)
     }
+
+    /// If the player is in sight, gets the first step of a path to them.
+    /// Paths may lead through closed doors, which get opened by walking into them.
+    fn get_step_towards_player(&self, entity_id: EntityId, world: &World) -> Option<Direction> {
+        let pos = world.get_entity(entity_id)?.pos()?;
+        let player_pos = world.get_entity(world.player_id)?.pos()?;
+
+        let in_sight = pos.distance_to(&player_pos) <= SIGHT_RADIUS as f32
+            && world.has_line_of_sight(&pos, &player_pos);
+        if !in_sight {
+            tracing::trace!(entity_id, %pos, %player_pos, "player not in sight");
+            return None;
+        }
+
+        let Some(path) = world.find_path(&pos, &player_pos, true) else {
+            tracing::debug!(entity_id, %pos, %player_pos, "no path to the player");
+            return None;
+        };
+        let next = *path.first()?;
+        Direction::from_delta(next.x - pos.x, next.y - pos.y)
+    }
 }
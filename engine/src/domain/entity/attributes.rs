@@ -1,8 +1,18 @@
-use serde::Serialize;
+use rand::Rng;
+use serde::{Deserialize, Serialize};
 use specta::Type;
 use ts_rs::TS;
 
-#[derive(Debug, Clone, Serialize, TS, Type)]
+/// Attribute points that add up to one point of a pool regenerated.
+const REGEN_BASE: u64 = 50;
+/// Fatigue gained for every point of stamina spent beyond what is left.
+const OVEREXERTION_FATIGUE: u64 = 5;
+/// Fatigue removed by a turn of rest.
+const REST_FATIGUE_RECOVERY: u64 = 5;
+/// Stamina recovered by a turn of rest.
+const REST_STAMINA_RECOVERY: u64 = 2;
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, TS, Type)]
 #[ts(export)]
 pub struct CoreAttributes {
     pub strength: u64,
@@ -41,7 +51,7 @@ pub struct Stats {
     pub hearing_threshold: u64,
 }
 
-#[derive(Debug, Clone, Serialize, TS, Type)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, TS, Type)]
 #[ts(export)]
 pub enum Exhaustion {
     WellRested,
@@ -51,22 +61,211 @@ pub enum Exhaustion {
     Exhausted,
 }
 
-#[derive(Debug, Clone, Serialize, TS, Type)]
+impl Exhaustion {
+    /// Gets the exhaustion level for an accumulated amount of fatigue.
+    pub fn from_fatigue(fatigue: u64) -> Self {
+        match fatigue {
+            0..20 => Exhaustion::WellRested,
+            20..100 => Exhaustion::Rested,
+            100..200 => Exhaustion::Normal,
+            200..300 => Exhaustion::Tired,
+            _ => Exhaustion::Exhausted,
+        }
+    }
+
+    /// The lowest fatigue that still counts as this level.
+    pub fn min_fatigue(&self) -> u64 {
+        match self {
+            Exhaustion::WellRested => 0,
+            Exhaustion::Rested => 20,
+            Exhaustion::Normal => 100,
+            Exhaustion::Tired => 200,
+            Exhaustion::Exhausted => 300,
+        }
+    }
+
+    /// Modifier applied to speed, both in combat and when scheduling turns.
+    pub fn speed_modifier(&self) -> i64 {
+        match self {
+            Exhaustion::WellRested | Exhaustion::Rested | Exhaustion::Normal => 0,
+            Exhaustion::Tired => -2,
+            Exhaustion::Exhausted => -4,
+        }
+    }
+
+    /// Modifier applied to attack rolls.
+    pub fn accuracy_modifier(&self) -> i64 {
+        match self {
+            Exhaustion::WellRested => 1,
+            Exhaustion::Rested | Exhaustion::Normal => 0,
+            Exhaustion::Tired => -2,
+            Exhaustion::Exhausted => -5,
+        }
+    }
+}
+
+/// A resource with a current value that never goes above `max` or below zero.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, TS, Type)]
+#[ts(export)]
+pub struct Pool {
+    pub current: u64,
+    pub max: u64,
+}
+
+impl Pool {
+    /// Creates a full pool.
+    pub fn new(max: u64) -> Self {
+        Self { current: max, max }
+    }
+
+    pub fn with_current(current: u64, max: u64) -> Self {
+        Self {
+            current: current.min(max),
+            max,
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.current == 0
+    }
+
+    pub fn is_full(&self) -> bool {
+        self.current >= self.max
+    }
+
+    /// Removes up to `amount` from the pool and returns how much was actually removed.
+    pub fn damage(&mut self, amount: u64) -> u64 {
+        let removed = amount.min(self.current);
+        self.current -= removed;
+        removed
+    }
+
+    /// Adds up to `amount` to the pool without exceeding `max` and returns how much was added.
+    pub fn restore(&mut self, amount: u64) -> u64 {
+        let added = amount.min(self.max.saturating_sub(self.current));
+        self.current += added;
+        added
+    }
+
+    pub fn fill(&mut self) {
+        self.current = self.max;
+    }
+
+    /// Changes the maximum, clamping the current value if needed.
+    pub fn set_max(&mut self, max: u64) {
+        self.max = max;
+        self.current = self.current.min(max);
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, TS, Type)]
 #[ts(export)]
 pub struct Status {
+    pub health: Pool,
+    pub stamina: Pool,
+    pub mana: Pool,
+    pub exhaustion: Exhaustion,
+    /// Accumulated tiredness. Acting raises it, resting lowers it.
+    pub fatigue: u64,
+    #[serde(default)]
+    pub regeneration: Regeneration,
+}
+
+/// How far each pool is towards regenerating its next point. Every turn an
+/// entity takes adds the pool's attribute, and every [`REGEN_BASE`] points
+/// add up to one point regenerated. Counting per entity keeps the rate the
+/// same no matter how many others take turns in between.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize, TS, Type)]
+#[ts(export)]
+pub struct Regeneration {
     pub health: u64,
     pub stamina: u64,
     pub mana: u64,
-    pub exhaustion: Exhaustion,
 }
 
+impl Status {
+    /// Creates a status with full pools sized from the given attributes:
+    /// durability for health, fortitude for stamina and magic for mana.
+    pub fn from_attributes(stats: &CoreAttributes) -> Self {
+        Self {
+            health: Pool::new(stats.durability),
+            stamina: Pool::new(stats.fortitude),
+            mana: Pool::new(stats.magic),
+            exhaustion: Exhaustion::Rested,
+            fatigue: Exhaustion::Rested.min_fatigue(),
+            regeneration: Regeneration::default(),
+        }
+    }
+
+    /// Regenerates the pools over one of the entity's turns. Higher attributes
+    /// regenerate faster.
+    pub fn regenerate(&mut self, stats: &CoreAttributes) {
+        let progress = &mut self.regeneration;
+        self.health
+            .restore(accumulate(&mut progress.health, stats.durability));
+        self.stamina
+            .restore(accumulate(&mut progress.stamina, stats.fortitude));
+        self.mana
+            .restore(accumulate(&mut progress.mana, stats.magic));
+    }
+
+    pub fn is_dead(&self) -> bool {
+        self.health.is_empty()
+    }
+
+    /// Spends stamina on an action. Every action adds a point of fatigue, and
+    /// stamina that isn't there is paid for with extra fatigue instead.
+    pub fn exert(&mut self, stamina_cost: u64) {
+        let spent = self.stamina.damage(stamina_cost);
+        let deficit = stamina_cost - spent;
+        self.add_fatigue(
+            deficit
+                .saturating_mul(OVEREXERTION_FATIGUE)
+                .saturating_add(1),
+        );
+    }
+
+    /// Spends a turn resting, recovering stamina and working off fatigue.
+    pub fn rest(&mut self) {
+        self.stamina.restore(REST_STAMINA_RECOVERY);
+        self.fatigue = self.fatigue.saturating_sub(REST_FATIGUE_RECOVERY);
+        self.exhaustion = Exhaustion::from_fatigue(self.fatigue);
+    }
+
+    fn add_fatigue(&mut self, amount: u64) {
+        self.fatigue = self.fatigue.saturating_add(amount);
+        self.exhaustion = Exhaustion::from_fatigue(self.fatigue);
+    }
+}
+
+/// Adds a turn's worth of `attribute` to `progress` and takes out the whole
+/// points it has added up to.
+fn accumulate(progress: &mut u64, attribute: u64) -> u64 {
+    *progress = progress.saturating_add(attribute);
+    let points = *progress / REGEN_BASE;
+    *progress %= REGEN_BASE;
+    points
+}
+
+/// How far a stat can stray below and above its usual value.
 pub struct StatVariance {
     pub low: u64,
     pub high: u64,
 }
 
+/// A stat's usual value and how much it varies.
 pub struct TemplateStat(pub u64, pub StatVariance);
 
+impl TemplateStat {
+    /// Rolls a value between the usual one less `low` and the usual one plus `high`.
+    pub fn roll_with<R: Rng + ?Sized>(&self, rng: &mut R) -> u64 {
+        let TemplateStat(usual, variance) = self;
+        rng.random_range(usual.saturating_sub(variance.low)..=usual.saturating_add(variance.high))
+    }
+}
+
+/// What a typical member of a race is like. The pools are added on top of
+/// what the creature's attributes give.
 pub struct CreatureTemplate {
     pub kind: CreatureRaceKind,
     pub max_health: TemplateStat,
@@ -75,7 +274,43 @@ pub struct CreatureTemplate {
     pub stats: CoreAttributes,
 }
 
+impl CreatureTemplate {
+    /// Creates a status with full pools sized from `stats` and the race's own
+    /// health, stamina and mana, rolled from `rng`.
+    pub fn roll_status<R: Rng + ?Sized>(&self, stats: &CoreAttributes, rng: &mut R) -> Status {
+        let base = Status::from_attributes(stats);
+        Status {
+            health: Pool::new(base.health.max + self.max_health.roll_with(rng)),
+            stamina: Pool::new(base.stamina.max + self.max_stamina.roll_with(rng)),
+            mana: Pool::new(base.mana.max + self.max_mana.roll_with(rng)),
+            ..base
+        }
+    }
+}
+
 pub enum CreatureRaceKind {
     Human,
     Goblin,
 }
+
+impl CreatureRaceKind {
+    pub fn template(&self) -> CreatureTemplate {
+        match self {
+            CreatureRaceKind::Human => CreatureTemplate {
+                kind: CreatureRaceKind::Human,
+                max_health: TemplateStat(2, StatVariance { low: 2, high: 2 }),
+                max_stamina: TemplateStat(2, StatVariance { low: 2, high: 2 }),
+                max_mana: TemplateStat(2, StatVariance { low: 2, high: 2 }),
+                stats: CoreAttributes::default(),
+            },
+            // Quick and hardy, but weak and with little talent for magic
+            CreatureRaceKind::Goblin => CreatureTemplate {
+                kind: CreatureRaceKind::Goblin,
+                max_health: TemplateStat(1, StatVariance { low: 1, high: 2 }),
+                max_stamina: TemplateStat(3, StatVariance { low: 1, high: 2 }),
+                max_mana: TemplateStat(0, StatVariance { low: 0, high: 1 }),
+                stats: CoreAttributes::new(9, 12, 9, 11, 8),
+            },
+        }
+    }
+}
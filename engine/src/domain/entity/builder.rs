@@ -0,0 +1,106 @@
+use super::{
+    ai::Ai,
+    attributes::{CoreAttributes, Status},
+    equipment::{Equipment, Inventory, ItemKind},
+    spells::SpellKind,
+    types::{SpeciesKind, StairDirection},
+    Entity, EntityId, EntityKind, Material,
+};
+use crate::domain::{world::World, world_position::WorldPosition};
+
+/// Puts an entity together one component at a time, before it gets an id
+/// and is spawned into a world.
+#[derive(Debug, Clone)]
+pub struct EntityBuilder {
+    entity: Entity,
+}
+
+impl EntityBuilder {
+    /// Starts an entity with no components besides its kind.
+    pub fn new(kind: EntityKind) -> Self {
+        Self {
+            entity: Entity::new(0, kind),
+        }
+    }
+
+    pub fn player(species: SpeciesKind) -> Self {
+        Self::new(EntityKind::Player { species })
+    }
+
+    pub fn npc(species: SpeciesKind) -> Self {
+        Self::new(EntityKind::Npc { species })
+    }
+
+    pub fn item(kind: ItemKind) -> Self {
+        Self::new(EntityKind::Item { kind })
+    }
+
+    pub fn wall(material: Material) -> Self {
+        Self::new(EntityKind::Wall { material })
+    }
+
+    pub fn floor(material: Material) -> Self {
+        Self::new(EntityKind::Floor { material })
+    }
+
+    pub fn stairs(material: Material, direction: StairDirection) -> Self {
+        Self::new(EntityKind::Stairs {
+            material,
+            direction,
+        })
+    }
+
+    /// Places the entity on the map. Leaving it off the map with `None` suits
+    /// items that start out carried.
+    pub fn at(mut self, pos: impl Into<Option<WorldPosition>>) -> Self {
+        self.entity.pos = pos.into();
+        self
+    }
+
+    pub fn stats(mut self, stats: CoreAttributes) -> Self {
+        self.entity.stats = Some(stats);
+        self
+    }
+
+    pub fn status(mut self, status: Status) -> Self {
+        self.entity.status = Some(status);
+        self
+    }
+
+    pub fn ai(mut self, ai: Ai) -> Self {
+        self.entity.ai = Some(ai);
+        self
+    }
+
+    pub fn inventory(mut self, inventory: Inventory) -> Self {
+        self.entity.inventory = Some(inventory);
+        self
+    }
+
+    pub fn equipment(mut self, equipment: Equipment) -> Self {
+        self.entity.equipment = Some(equipment);
+        self
+    }
+
+    pub fn spells(mut self, spells: Vec<SpellKind>) -> Self {
+        self.entity.spells = spells;
+        self
+    }
+
+    /// Whether the entity will take turns of its own once spawned.
+    pub fn can_act(&self) -> bool {
+        self.entity.ai.is_some()
+    }
+
+    /// Finishes the entity with the given id, without adding it to a world.
+    pub fn build(self, id: EntityId) -> Entity {
+        Entity { id, ..self.entity }
+    }
+
+    /// Adds the entity to the world under the next free id and returns the id.
+    pub fn spawn(self, world: &mut World) -> EntityId {
+        let id = world.get_next_entity_id();
+        world.add_entity(self.build(id));
+        id
+    }
+}
@@ -1,21 +1,312 @@
-use serde::Serialize;
+use rand::Rng;
+use serde::{Deserialize, Serialize};
 use specta::Type;
 use ts_rs::TS;
 
-use crate::core::types::DieRoll;
+use super::{equipment::ItemKind, terrain, Entity, EntityId, EntityKind};
+use crate::{
+    core::dice::{Dice, DieRoll},
+    domain::world::World,
+};
 
-#[derive(PartialEq, Clone, Debug, Serialize, TS, Type)]
+#[derive(PartialEq, Clone, Debug, Serialize, Deserialize, TS, Type)]
 #[ts(export)]
 pub enum DamageType {
     Slice,
     Pierce,
     Blunt,
     Fire,
+    Poison,
 }
 
-#[derive(PartialEq, Clone, Debug, Serialize, TS, Type)]
+#[derive(PartialEq, Clone, Debug, Serialize, Deserialize, TS, Type)]
 #[ts(export)]
 pub struct Damage {
     pub damage_type: DamageType,
     pub damage: DieRoll,
 }
+
+/// How strongly something reacts to a type of damage.
+#[derive(PartialEq, Eq, Clone, Copy, Debug, Serialize, TS, Type)]
+#[ts(export)]
+pub enum Resistance {
+    Immune,
+    Resistant,
+    Normal,
+    Vulnerable,
+}
+
+impl Resistance {
+    /// Percentage of incoming damage that gets through.
+    pub fn multiplier(&self) -> u64 {
+        match self {
+            Resistance::Immune => 0,
+            Resistance::Resistant => 50,
+            Resistance::Normal => 100,
+            Resistance::Vulnerable => 200,
+        }
+    }
+}
+
+impl Damage {
+    /// Damage dealt by an attacker with nothing in hand.
+    pub fn unarmed() -> Self {
+        Damage {
+            damage_type: DamageType::Blunt,
+            damage: DieRoll::new(1, Dice::Flat(1), 0),
+        }
+    }
+
+    pub fn roll_with<R: Rng + ?Sized>(&self, rng: &mut R) -> u64 {
+        self.damage.roll_with(rng).max(0) as u64
+    }
+}
+
+#[derive(PartialEq, Clone, Debug, Serialize, TS, Type)]
+#[ts(export)]
+pub struct DealtDamage {
+    pub damage_type: DamageType,
+    pub amount: u64,
+}
+
+/// Gets the damage an entity attacks with in melee: the damage of every
+/// equipped melee weapon, or its bare hands if it has none.
+pub fn get_attack_damage(world: &World, attacker_id: EntityId) -> Vec<Damage> {
+    let damage: Vec<Damage> = world
+        .get_equipped_items(attacker_id)
+        .into_iter()
+        .flat_map(|item| match item.kind() {
+            EntityKind::Item {
+                kind:
+                    ItemKind::Weapon {
+                        damage,
+                        range: None,
+                        ..
+                    },
+            } => damage.clone(),
+            _ => vec![],
+        })
+        .collect();
+
+    if damage.is_empty() {
+        vec![Damage::unarmed()]
+    } else {
+        damage
+    }
+}
+
+/// Gets the equipped ranged weapon an entity shoots with, along with its damage and range.
+pub fn get_ranged_weapon(world: &World, attacker_id: EntityId) -> Option<(Vec<Damage>, i32)> {
+    world
+        .get_equipped_items(attacker_id)
+        .into_iter()
+        .find_map(|item| match item.kind() {
+            EntityKind::Item {
+                kind:
+                    ItemKind::Weapon {
+                        damage,
+                        range: Some(range),
+                        ..
+                    },
+            } => Some((damage.clone(), *range)),
+            _ => None,
+        })
+}
+
+/// Damage an item deals when thrown. Weapons hit as they would in hand,
+/// anything else just bonks.
+pub fn get_thrown_damage(item: &Entity) -> Vec<Damage> {
+    match item.kind() {
+        EntityKind::Item {
+            kind: ItemKind::Weapon { damage, .. },
+        } => damage.clone(),
+        _ => vec![Damage::unarmed()],
+    }
+}
+
+/// Gets the total defense of the armor an entity is wearing.
+pub fn get_defense(world: &World, defender_id: EntityId) -> u64 {
+    world
+        .get_equipped_items(defender_id)
+        .into_iter()
+        .map(|item| match item.kind() {
+            EntityKind::Item {
+                kind: ItemKind::Armor { defense },
+            } => *defense,
+            _ => 0,
+        })
+        .sum()
+}
+
+/// A natural roll that always hits and deals double damage.
+const CRITICAL_HIT: i64 = 20;
+/// A natural roll that always misses.
+const FUMBLE: i64 = 1;
+/// Defense class of a creature before its attributes are counted.
+const BASE_DEFENSE_CLASS: i64 = 10;
+
+#[derive(PartialEq, Eq, Clone, Copy, Debug, Serialize, TS, Type)]
+#[ts(export)]
+pub enum AttackOutcome {
+    Hit,
+    CriticalHit,
+    Miss,
+    Fumble,
+}
+
+impl AttackOutcome {
+    pub fn is_hit(&self) -> bool {
+        matches!(self, AttackOutcome::Hit | AttackOutcome::CriticalHit)
+    }
+}
+
+/// The breakdown of a single attack roll.
+#[derive(PartialEq, Clone, Debug, Serialize, TS, Type)]
+#[ts(export)]
+pub struct AttackRoll {
+    /// What the d20 showed.
+    pub natural: i64,
+    pub attack_bonus: i64,
+    pub total: i64,
+    /// What the total had to reach to hit.
+    pub defense_class: i64,
+    pub outcome: AttackOutcome,
+}
+
+#[derive(PartialEq, Clone, Debug)]
+pub struct AttackResult {
+    pub roll: AttackRoll,
+    pub damage: Vec<DealtDamage>,
+}
+
+/// Bonus to attack rolls from strength and speed, reduced by exhaustion.
+pub fn attack_bonus(attacker: &Entity) -> i64 {
+    (attacker.effective_stats().strength + attacker.effective_speed()) as i64 / 4
+        + attacker
+            .status
+            .as_ref()
+            .map_or(0, |status| status.exhaustion.accuracy_modifier())
+}
+
+/// The total an attack roll must reach to hit, from speed and durability.
+pub fn defense_class(defender: &Entity) -> i64 {
+    BASE_DEFENSE_CLASS
+        + (defender.effective_speed() + defender.effective_stats().durability) as i64 / 4
+}
+
+/// Rolls a d20 to see whether the attacker hits the defender.
+pub fn roll_to_hit<R: Rng + ?Sized>(
+    attacker: &Entity,
+    defender: &Entity,
+    rng: &mut R,
+) -> AttackRoll {
+    let natural = DieRoll::new(1, Dice::D20, 0).roll_with(rng);
+    let attack_bonus = attack_bonus(attacker);
+    let total = natural + attack_bonus;
+    let defense_class = defense_class(defender);
+
+    let outcome = match natural {
+        CRITICAL_HIT => AttackOutcome::CriticalHit,
+        FUMBLE => AttackOutcome::Fumble,
+        _ if total >= defense_class => AttackOutcome::Hit,
+        _ => AttackOutcome::Miss,
+    };
+
+    AttackRoll {
+        natural,
+        attack_bonus,
+        total,
+        defense_class,
+        outcome,
+    }
+}
+
+/// Rolls to hit and, on a hit, rolls the attacker's damage and mitigates it.
+/// Critical hits roll the damage dice twice. The defender's armor is
+/// subtracted first, used up by the damage components in order, and what is
+/// left is scaled by the defender's resistance to each damage type.
+pub fn resolve_attack<R: Rng + ?Sized>(
+    world: &World,
+    attacker_id: EntityId,
+    defender_id: EntityId,
+    rng: &mut R,
+) -> Option<AttackResult> {
+    let damage = get_attack_damage(world, attacker_id);
+    resolve_attack_with(world, attacker_id, defender_id, damage, rng)
+}
+
+/// Resolves an attack like [`resolve_attack`], but with the given damage
+/// instead of the attacker's melee damage. Used for shots and thrown items.
+pub fn resolve_attack_with<R: Rng + ?Sized>(
+    world: &World,
+    attacker_id: EntityId,
+    defender_id: EntityId,
+    damage: Vec<Damage>,
+    rng: &mut R,
+) -> Option<AttackResult> {
+    let attacker = world.get_entity(attacker_id)?;
+    let defender = world.get_entity(defender_id)?;
+
+    let roll = roll_to_hit(attacker, defender, rng);
+    if !roll.outcome.is_hit() {
+        return Some(AttackResult {
+            roll,
+            damage: vec![],
+        });
+    }
+
+    let mut defense = get_defense(world, defender_id);
+    let damage = damage
+        .into_iter()
+        .map(|damage| {
+            let rolled = match roll.outcome {
+                AttackOutcome::CriticalHit => {
+                    damage.roll_with(rng).saturating_add(damage.roll_with(rng))
+                }
+                _ => damage.roll_with(rng),
+            };
+            let absorbed = rolled.min(defense);
+            defense -= absorbed;
+            DealtDamage {
+                amount: mitigate(defender, &damage.damage_type, rolled - absorbed),
+                damage_type: damage.damage_type,
+            }
+        })
+        .collect();
+
+    Some(AttackResult { roll, damage })
+}
+
+/// Rolls damage against a wall or door. Terrain can't dodge, so there is no
+/// attack roll, but its hardness absorbs part of each hit and only blunt and
+/// fire damage wear it down.
+pub fn resolve_terrain_damage_with<R: Rng + ?Sized>(
+    world: &World,
+    target_id: EntityId,
+    damage: &[Damage],
+    rng: &mut R,
+) -> Vec<DealtDamage> {
+    let Some(target) = world.get_entity(target_id).filter(|e| e.is_terrain()) else {
+        return vec![];
+    };
+
+    let hardness = target.hardness();
+    damage
+        .iter()
+        .filter(|damage| terrain::damages_terrain(&damage.damage_type))
+        .map(|damage| {
+            let rolled = damage.roll_with(rng).saturating_sub(hardness);
+            DealtDamage {
+                amount: mitigate(target, &damage.damage_type, rolled),
+                damage_type: damage.damage_type.clone(),
+            }
+        })
+        .collect()
+}
+
+/// Scales damage by the target's resistance to its type. Worked out in 128
+/// bits, so stacked vulnerabilities on a huge hit top out instead of overflowing.
+pub fn mitigate(target: &Entity, damage_type: &DamageType, amount: u64) -> u64 {
+    let scaled = amount as u128 * target.damage_multiplier(damage_type) as u128 / 100;
+    u64::try_from(scaled).unwrap_or(u64::MAX)
+}
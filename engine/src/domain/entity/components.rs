@@ -0,0 +1,51 @@
+//! The optional parts entities are made of. A wall has attributes and health
+//! so it can be knocked down, but no stamina, AI or inventory, while a floor
+//! tile or an item lying around is little more than a position.
+use super::{
+    ai::Ai,
+    attributes::{CoreAttributes, Status},
+    equipment::{Equipment, Inventory},
+    Entity,
+};
+use crate::domain::world_position::WorldPosition;
+
+/// A part an entity may or may not have. Systems ask for the components they
+/// work with and leave alone the entities that lack them.
+pub trait Component: Sized + 'static {
+    fn get(entity: &Entity) -> Option<&Self>;
+}
+
+macro_rules! component {
+    ($component:ty, $field:ident) => {
+        impl Component for $component {
+            fn get(entity: &Entity) -> Option<&Self> {
+                entity.$field.as_ref()
+            }
+        }
+    };
+}
+
+component!(WorldPosition, pos);
+component!(CoreAttributes, stats);
+component!(Status, status);
+component!(Ai, ai);
+component!(Inventory, inventory);
+component!(Equipment, equipment);
+
+impl Entity {
+    pub fn get<C: Component>(&self) -> Option<&C> {
+        C::get(self)
+    }
+
+    /// Whether the entity has run out of health. Entities without a status can't die.
+    pub fn is_dead(&self) -> bool {
+        self.status.as_ref().is_some_and(|status| status.is_dead())
+    }
+
+    /// Spends stamina on an action, if the entity has any to spend.
+    pub fn exert(&mut self, stamina_cost: u64) {
+        if let Some(status) = self.status.as_mut() {
+            status.exert(stamina_cost);
+        }
+    }
+}
@@ -0,0 +1,52 @@
+use super::{types::KeyId, Entity, EntityKind};
+
+impl Entity {
+    pub fn is_door(&self) -> bool {
+        matches!(self.kind, EntityKind::Door { .. })
+    }
+
+    pub fn is_closed_door(&self) -> bool {
+        matches!(self.kind, EntityKind::Door { open: false, .. })
+    }
+
+    /// The key needed to open the door, if it is a locked door.
+    pub fn door_lock(&self) -> Option<KeyId> {
+        match self.kind {
+            EntityKind::Door { lock, .. } => lock,
+            _ => None,
+        }
+    }
+
+    /// Opens or closes a door, updating whether its material blocks movement
+    /// and vision. Locked doors stay shut. Returns whether the door changed.
+    pub fn set_door_open(&mut self, open: bool) -> bool {
+        let EntityKind::Door {
+            material,
+            open: is_open,
+            lock,
+        } = &mut self.kind
+        else {
+            return false;
+        };
+        if *is_open == open || (open && lock.is_some()) {
+            return false;
+        }
+
+        let closed_material = material.kind.get_material();
+        *is_open = open;
+        material.blocks_movement = !open && closed_material.blocks_movement;
+        material.blocks_vision = !open && closed_material.blocks_vision;
+        true
+    }
+
+    /// Unlocks a door with the given key. Returns whether the key fit.
+    pub fn unlock_door(&mut self, key: KeyId) -> bool {
+        match &mut self.kind {
+            EntityKind::Door { lock, .. } if *lock == Some(key) => {
+                *lock = None;
+                true
+            }
+            _ => false,
+        }
+    }
+}
@@ -0,0 +1,160 @@
+use serde::{Deserialize, Serialize};
+use specta::Type;
+use ts_rs::TS;
+
+use super::{attributes::CoreAttributes, combat::DamageType, Entity, EntityId};
+
+/// How long a creature burns after being hit with fire.
+const BURNING_DURATION: u64 = 3;
+
+/// Changes to core attributes from a buff or debuff.
+#[derive(PartialEq, Eq, Clone, Debug, Default, Serialize, Deserialize, TS, Type)]
+#[ts(export)]
+pub struct AttributeModifier {
+    pub strength: i64,
+    pub speed: i64,
+    pub durability: i64,
+    pub fortitude: i64,
+    pub magic: i64,
+}
+
+impl AttributeModifier {
+    pub fn apply(&self, stats: &CoreAttributes) -> CoreAttributes {
+        CoreAttributes {
+            strength: stats.strength.saturating_add_signed(self.strength),
+            speed: stats.speed.saturating_add_signed(self.speed),
+            durability: stats.durability.saturating_add_signed(self.durability),
+            fortitude: stats.fortitude.saturating_add_signed(self.fortitude),
+            magic: stats.magic.saturating_add_signed(self.magic),
+        }
+    }
+}
+
+#[derive(PartialEq, Clone, Debug, Serialize, Deserialize, TS, Type)]
+#[serde(tag = "type")]
+#[ts(export)]
+pub enum EffectKind {
+    Poison { damage_per_turn: u64 },
+    Burning { damage_per_turn: u64 },
+    Stun,
+    Regeneration { health_per_turn: u64 },
+    Modifier { modifier: AttributeModifier },
+}
+
+/// What happens when an effect is applied to an entity that already has one of the same kind.
+#[derive(PartialEq, Eq, Clone, Copy, Debug)]
+pub enum Stacking {
+    /// The existing effect lasts for whichever duration is longer.
+    Refresh,
+    /// The existing effect gains a stack, up to `max`, and its duration is refreshed.
+    Intensify { max: u64 },
+    /// Both effects are kept and run on their own.
+    Independent,
+}
+
+impl EffectKind {
+    pub fn stacking(&self) -> Stacking {
+        match self {
+            EffectKind::Poison { .. } => Stacking::Intensify { max: 5 },
+            EffectKind::Burning { .. } | EffectKind::Stun | EffectKind::Regeneration { .. } => {
+                Stacking::Refresh
+            }
+            EffectKind::Modifier { .. } => Stacking::Independent,
+        }
+    }
+
+    fn is_same_kind(&self, other: &EffectKind) -> bool {
+        std::mem::discriminant(self) == std::mem::discriminant(other)
+    }
+}
+
+#[derive(PartialEq, Clone, Debug, Serialize, Deserialize, TS, Type)]
+#[ts(export)]
+pub struct StatusEffect {
+    pub kind: EffectKind,
+    pub remaining_turns: u64,
+    pub stacks: u64,
+    /// Whoever caused the effect, if anyone.
+    pub source: Option<EntityId>,
+}
+
+impl StatusEffect {
+    pub fn new(kind: EffectKind, duration: u64, source: Option<EntityId>) -> Self {
+        Self {
+            kind,
+            remaining_turns: duration,
+            stacks: 1,
+            source,
+        }
+    }
+
+    /// Damage the effect deals each turn, if it deals any.
+    pub fn damage_per_turn(&self) -> Option<(DamageType, u64)> {
+        match self.kind {
+            EffectKind::Poison { damage_per_turn } => {
+                Some((DamageType::Poison, damage_per_turn * self.stacks))
+            }
+            EffectKind::Burning { damage_per_turn } => {
+                Some((DamageType::Fire, damage_per_turn * self.stacks))
+            }
+            _ => None,
+        }
+    }
+}
+
+/// The effect a hit of the given damage type leaves behind, if any.
+pub fn get_on_hit_effect(
+    damage_type: &DamageType,
+    source: Option<EntityId>,
+) -> Option<StatusEffect> {
+    match damage_type {
+        DamageType::Fire => Some(StatusEffect::new(
+            EffectKind::Burning { damage_per_turn: 1 },
+            BURNING_DURATION,
+            source,
+        )),
+        _ => None,
+    }
+}
+
+impl Entity {
+    /// Adds an effect following the stacking rules of its kind.
+    pub fn add_effect(&mut self, effect: StatusEffect) {
+        let existing = self
+            .effects
+            .iter_mut()
+            .find(|e| e.kind.is_same_kind(&effect.kind));
+
+        match (effect.kind.stacking(), existing) {
+            (Stacking::Refresh, Some(existing)) => {
+                existing.remaining_turns = existing.remaining_turns.max(effect.remaining_turns);
+                existing.kind = effect.kind;
+            }
+            (Stacking::Intensify { max }, Some(existing)) => {
+                existing.stacks = (existing.stacks + effect.stacks).min(max);
+                existing.remaining_turns = existing.remaining_turns.max(effect.remaining_turns);
+            }
+            _ => self.effects.push(effect),
+        }
+    }
+
+    pub fn is_stunned(&self) -> bool {
+        self.effects.iter().any(|e| e.kind == EffectKind::Stun)
+    }
+
+    /// Core attributes with every active buff and debuff applied. Entities
+    /// without attributes count as having none at all.
+    pub fn effective_stats(&self) -> CoreAttributes {
+        let base = self
+            .stats
+            .clone()
+            .unwrap_or_else(|| CoreAttributes::new(0, 0, 0, 0, 0));
+        self.effects
+            .iter()
+            .filter_map(|e| match &e.kind {
+                EffectKind::Modifier { modifier } => Some(modifier),
+                _ => None,
+            })
+            .fold(base, |stats, modifier| modifier.apply(&stats))
+    }
+}
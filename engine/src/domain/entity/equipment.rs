@@ -1,12 +1,25 @@
-use serde::Serialize;
+use serde::{Deserialize, Serialize};
 use specta::Type;
 use ts_rs::TS;
 
 use super::combat::Damage;
-use super::types::EntityId;
+use super::types::{EntityId, KeyId};
 
-pub struct Armor {}
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, TS, Type)]
+#[ts(export)]
+pub enum Hand {
+    Left,
+    Right,
+}
 
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize, TS, Type)]
+#[ts(export)]
+pub struct Armor {
+    pub body: Option<EntityId>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, TS, Type)]
+#[ts(export)]
 pub enum HandsEquipment {
     TwoHanded(Option<EntityId>),
     OneHanded {
@@ -15,18 +28,160 @@ pub enum HandsEquipment {
     },
 }
 
+impl Default for HandsEquipment {
+    fn default() -> Self {
+        HandsEquipment::OneHanded {
+            left: None,
+            right: None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize, TS, Type)]
+#[ts(export)]
 pub struct Equipment {
     pub armor: Armor,
     pub hands: HandsEquipment,
 }
 
+impl Equipment {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Gets the ids of everything currently equipped.
+    pub fn equipped_items(&self) -> Vec<EntityId> {
+        let hands = match self.hands {
+            HandsEquipment::TwoHanded(item) => vec![item],
+            HandsEquipment::OneHanded { left, right } => vec![left, right],
+        };
+        hands
+            .into_iter()
+            .chain([self.armor.body])
+            .flatten()
+            .collect()
+    }
+
+    pub fn is_equipped(&self, item_id: EntityId) -> bool {
+        self.equipped_items().contains(&item_id)
+    }
+
+    /// Puts a weapon in hand. A two-handed weapon needs both hands free, and a
+    /// one-handed weapon goes in the requested hand or, without a request, in
+    /// whichever hand is free. Returns whether the weapon was equipped.
+    pub fn equip_weapon(
+        &mut self,
+        item_id: EntityId,
+        two_handed: bool,
+        hand: Option<Hand>,
+    ) -> bool {
+        let hands_free = matches!(
+            self.hands,
+            HandsEquipment::TwoHanded(None)
+                | HandsEquipment::OneHanded {
+                    left: None,
+                    right: None
+                }
+        );
+
+        if two_handed {
+            if hands_free && hand.is_none() {
+                self.hands = HandsEquipment::TwoHanded(Some(item_id));
+                return true;
+            }
+            return false;
+        }
+
+        if let HandsEquipment::TwoHanded(None) = self.hands {
+            self.hands = HandsEquipment::default();
+        }
+
+        match &mut self.hands {
+            HandsEquipment::TwoHanded(_) => false,
+            HandsEquipment::OneHanded { left, right } => {
+                let slot = match hand {
+                    Some(Hand::Left) => left,
+                    Some(Hand::Right) => right,
+                    None if right.is_none() => right,
+                    None => left,
+                };
+                if slot.is_none() {
+                    *slot = Some(item_id);
+                    true
+                } else {
+                    false
+                }
+            }
+        }
+    }
+
+    /// Puts on a piece of armor if nothing is worn yet. Returns whether the armor was equipped.
+    pub fn equip_armor(&mut self, item_id: EntityId) -> bool {
+        if self.armor.body.is_some() {
+            return false;
+        }
+        self.armor.body = Some(item_id);
+        true
+    }
+
+    /// Takes off an equipped item. Returns whether it was equipped.
+    pub fn unequip(&mut self, item_id: EntityId) -> bool {
+        let slots = match &mut self.hands {
+            HandsEquipment::TwoHanded(item) => vec![item],
+            HandsEquipment::OneHanded { left, right } => vec![left, right],
+        };
+        for slot in slots.into_iter().chain([&mut self.armor.body]) {
+            if *slot == Some(item_id) {
+                *slot = None;
+                return true;
+            }
+        }
+        false
+    }
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize, TS, Type)]
+#[ts(export)]
 pub struct Inventory {
     pub items: Vec<EntityId>,
 }
 
-#[derive(PartialEq, Clone, Debug, Serialize, TS, Type)]
+impl Inventory {
+    pub fn new() -> Self {
+        Self { items: vec![] }
+    }
+
+    pub fn contains(&self, item_id: EntityId) -> bool {
+        self.items.contains(&item_id)
+    }
+
+    pub fn add(&mut self, item_id: EntityId) {
+        if !self.contains(item_id) {
+            self.items.push(item_id);
+        }
+    }
+
+    /// Removes an item, returning whether it was in the inventory.
+    pub fn remove(&mut self, item_id: EntityId) -> bool {
+        let len_before = self.items.len();
+        self.items.retain(|&id| id != item_id);
+        self.items.len() != len_before
+    }
+}
+
+#[derive(PartialEq, Clone, Debug, Serialize, Deserialize, TS, Type)]
 #[ts(export)]
 pub enum ItemKind {
-    Weapon { damage: Vec<Damage> },
-    Armor { defense: u64 },
+    Weapon {
+        damage: Vec<Damage>,
+        two_handed: bool,
+        /// How far the weapon shoots, or `None` for melee weapons.
+        range: Option<i32>,
+    },
+    Armor {
+        defense: u64,
+    },
+    Key {
+        key_id: KeyId,
+    },
 }
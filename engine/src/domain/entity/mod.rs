@@ -1,13 +1,27 @@
 pub mod ai;
 pub mod attributes;
+pub mod builder;
 pub mod combat;
+pub mod components;
+pub mod doors;
+pub mod effects;
 pub mod equipment;
+pub mod spells;
+pub mod terrain;
 pub mod types;
 
 pub use attributes::{
-    CoreAttributes, CreatureRaceKind, CreatureTemplate, Exhaustion, StatVariance, Stats, Status,
-    TemplateStat,
+    CoreAttributes, CreatureRaceKind, CreatureTemplate, Exhaustion, Pool, Regeneration,
+    StatVariance, Stats, Status, TemplateStat,
+};
+pub use builder::EntityBuilder;
+pub use combat::{
+    AttackOutcome, AttackResult, AttackRoll, Damage, DamageType, DealtDamage, Resistance,
+};
+pub use components::Component;
+pub use effects::{AttributeModifier, EffectKind, Stacking, StatusEffect};
+pub use equipment::{Armor, Equipment, Hand, HandsEquipment, Inventory, ItemKind};
+pub use spells::{Spell, SpellEffect, SpellKind, TargetShape};
+pub use types::{
+    Entity, EntityId, EntityKind, KeyId, Material, MaterialKind, Size, SpeciesKind, StairDirection,
 };
-pub use combat::{Damage, DamageType};
-pub use equipment::{Armor, Equipment, HandsEquipment, Inventory, ItemKind};
-pub use types::{Entity, EntityId, EntityKind, Material, MaterialKind, Size, SpeciesKind};
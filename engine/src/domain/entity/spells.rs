@@ -0,0 +1,220 @@
+use serde::{Deserialize, Serialize};
+use specta::Type;
+use ts_rs::TS;
+
+use super::{
+    combat::{Damage, DamageType},
+    effects::{AttributeModifier, EffectKind, StatusEffect},
+    EntityId,
+};
+use crate::{
+    core::dice::{Dice, DieRoll},
+    domain::{world::World, world_position::WorldPosition},
+};
+
+#[derive(PartialEq, Eq, Clone, Copy, Debug, Serialize, Deserialize, TS, Type)]
+#[ts(export)]
+pub enum SpellKind {
+    Firebolt,
+    Fireball,
+    FrostCone,
+    FlameLance,
+    Daze,
+    Weaken,
+    Mend,
+}
+
+/// The area a spell affects around its target.
+#[derive(PartialEq, Clone, Debug, Serialize, TS, Type)]
+#[serde(tag = "type")]
+#[ts(export)]
+pub enum TargetShape {
+    /// Only the targeted tile.
+    Single,
+    /// Every tile from the caster to the target.
+    Line,
+    /// A wedge spreading out from the caster towards the target.
+    Cone { half_angle_degrees: u32 },
+    /// Every tile within `radius` of the target.
+    Radius { radius: i32 },
+}
+
+#[derive(PartialEq, Clone, Debug, Serialize, TS, Type)]
+#[ts(export)]
+pub struct SpellEffect {
+    pub kind: EffectKind,
+    pub duration: u64,
+}
+
+#[derive(PartialEq, Clone, Debug, Serialize, TS, Type)]
+#[ts(export)]
+pub struct Spell {
+    pub kind: SpellKind,
+    pub mana_cost: u64,
+    /// How far from the caster the target may be.
+    pub range: i32,
+    pub shape: TargetShape,
+    pub damage: Vec<Damage>,
+    pub effects: Vec<SpellEffect>,
+}
+
+impl SpellKind {
+    pub fn get_spell(&self) -> Spell {
+        match self {
+            SpellKind::Firebolt => Spell {
+                kind: *self,
+                mana_cost: 3,
+                range: 8,
+                shape: TargetShape::Single,
+                damage: vec![Damage {
+                    damage_type: DamageType::Fire,
+                    damage: DieRoll::new(1, Dice::D6, 1),
+                }],
+                effects: vec![],
+            },
+            SpellKind::Fireball => Spell {
+                kind: *self,
+                mana_cost: 6,
+                range: 6,
+                shape: TargetShape::Radius { radius: 1 },
+                damage: vec![Damage {
+                    damage_type: DamageType::Fire,
+                    damage: DieRoll::new(2, Dice::D6, 0),
+                }],
+                effects: vec![],
+            },
+            SpellKind::FrostCone => Spell {
+                kind: *self,
+                mana_cost: 5,
+                range: 4,
+                shape: TargetShape::Cone {
+                    half_angle_degrees: 30,
+                },
+                damage: vec![Damage {
+                    damage_type: DamageType::Pierce,
+                    damage: DieRoll::new(1, Dice::D6, 0),
+                }],
+                effects: vec![SpellEffect {
+                    kind: EffectKind::Modifier {
+                        modifier: AttributeModifier {
+                            speed: -3,
+                            ..Default::default()
+                        },
+                    },
+                    duration: 3,
+                }],
+            },
+            SpellKind::FlameLance => Spell {
+                kind: *self,
+                mana_cost: 5,
+                range: 8,
+                shape: TargetShape::Line,
+                damage: vec![Damage {
+                    damage_type: DamageType::Fire,
+                    damage: DieRoll::new(1, Dice::D8, 0),
+                }],
+                effects: vec![],
+            },
+            SpellKind::Daze => Spell {
+                kind: *self,
+                mana_cost: 4,
+                range: 5,
+                shape: TargetShape::Single,
+                damage: vec![],
+                effects: vec![SpellEffect {
+                    kind: EffectKind::Stun,
+                    duration: 2,
+                }],
+            },
+            SpellKind::Weaken => Spell {
+                kind: *self,
+                mana_cost: 3,
+                range: 6,
+                shape: TargetShape::Single,
+                damage: vec![],
+                effects: vec![SpellEffect {
+                    kind: EffectKind::Modifier {
+                        modifier: AttributeModifier {
+                            strength: -3,
+                            durability: -2,
+                            ..Default::default()
+                        },
+                    },
+                    duration: 5,
+                }],
+            },
+            SpellKind::Mend => Spell {
+                kind: *self,
+                mana_cost: 4,
+                range: 1,
+                shape: TargetShape::Single,
+                damage: vec![],
+                effects: vec![SpellEffect {
+                    kind: EffectKind::Regeneration { health_per_turn: 1 },
+                    duration: 5,
+                }],
+            },
+        }
+    }
+}
+
+impl Spell {
+    /// Whether `target` is close enough to `from` and in sight of it.
+    pub fn can_target(&self, world: &World, from: &WorldPosition, target: &WorldPosition) -> bool {
+        from.distance_to(target) <= self.range as f32 && world.has_line_of_sight(from, target)
+    }
+
+    /// Gets every tile the spell affects when cast from `from` at `target`.
+    pub fn get_affected_positions(
+        &self,
+        world: &World,
+        from: &WorldPosition,
+        target: &WorldPosition,
+    ) -> Vec<WorldPosition> {
+        match self.shape {
+            TargetShape::Single => vec![*target],
+            TargetShape::Line => world
+                .get_line(from, target)
+                .into_iter()
+                .skip(1)
+                .take_while(|pos| pos == target || !world.blocks_vision(pos))
+                .collect(),
+            TargetShape::Cone { half_angle_degrees } => {
+                let aim = ((target.y - from.y) as f32).atan2((target.x - from.x) as f32);
+                let half_angle = (half_angle_degrees as f32).to_radians();
+                positions_within(from, self.range)
+                    .into_iter()
+                    .filter(|pos| pos != from)
+                    .filter(|pos| {
+                        let angle = ((pos.y - from.y) as f32).atan2((pos.x - from.x) as f32);
+                        let difference = (angle - aim + std::f32::consts::PI)
+                            .rem_euclid(std::f32::consts::TAU)
+                            - std::f32::consts::PI;
+                        difference.abs() <= half_angle
+                    })
+                    .filter(|pos| world.has_line_of_sight(from, pos))
+                    .collect()
+            }
+            TargetShape::Radius { radius } => positions_within(target, radius)
+                .into_iter()
+                .filter(|pos| world.has_line_of_sight(target, pos))
+                .collect(),
+        }
+    }
+
+    /// The status effects the spell leaves on everything it hits.
+    pub fn get_status_effects(&self, caster_id: EntityId) -> Vec<StatusEffect> {
+        self.effects
+            .iter()
+            .map(|effect| StatusEffect::new(effect.kind.clone(), effect.duration, Some(caster_id)))
+            .collect()
+    }
+}
+
+fn positions_within(center: &WorldPosition, radius: i32) -> Vec<WorldPosition> {
+    (-radius..=radius)
+        .flat_map(|dy| (-radius..=radius).map(move |dx| (dx, dy)))
+        .map(|(dx, dy)| WorldPosition::new(center.x + dx, center.y + dy))
+        .filter(|pos| center.distance_to(pos) <= radius as f32)
+        .collect()
+}
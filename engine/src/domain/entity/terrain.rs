@@ -0,0 +1,36 @@
+use super::{combat::DamageType, Entity, EntityKind};
+
+impl Entity {
+    /// Whether the entity is a wall or door that can be worn down.
+    pub fn is_terrain(&self) -> bool {
+        matches!(self.kind, EntityKind::Wall { .. } | EntityKind::Door { .. })
+    }
+
+    /// How much of every hit the terrain shrugs off before its material is considered.
+    pub fn hardness(&self) -> u64 {
+        self.stats.as_ref().map_or(0, |stats| stats.durability / 5)
+    }
+
+    /// Knocks the terrain down, leaving rubble that can be walked and seen through.
+    /// Returns whether there was anything to knock down.
+    pub fn collapse(&mut self) -> bool {
+        let material = match &self.kind {
+            EntityKind::Wall { material } | EntityKind::Door { material, .. } => material.clone(),
+            _ => return false,
+        };
+
+        self.kind = EntityKind::Floor {
+            material: super::Material {
+                blocks_vision: false,
+                blocks_movement: false,
+                ..material
+            },
+        };
+        true
+    }
+}
+
+/// Only crushing blows and fire can wear down terrain.
+pub fn damages_terrain(damage_type: &DamageType) -> bool {
+    matches!(damage_type, DamageType::Blunt | DamageType::Fire)
+}
@@ -1,13 +1,19 @@
-use serde::Serialize;
+use serde::{Deserialize, Serialize};
 use specta::Type;
 use ts_rs::TS;
 
 use super::super::world_position::WorldPosition;
 use super::ai::Ai;
-use super::attributes::{CoreAttributes, Status};
+use super::attributes::{CoreAttributes, CreatureRaceKind, Status};
+use super::combat::{DamageType, Resistance};
+use super::effects::StatusEffect;
+use super::equipment::{Equipment, Inventory};
+use super::spells::SpellKind;
 use crate::core::types::Direction;
 
 pub type EntityId = usize;
+/// Identifies which keys open which locks.
+pub type KeyId = u64;
 
 pub enum Size {
     Tiny,
@@ -16,21 +22,40 @@ pub enum Size {
     Large,
 }
 
-#[derive(PartialEq, Clone, Debug, Serialize, TS, Type)]
+#[derive(PartialEq, Clone, Debug, Serialize, Deserialize, TS, Type)]
 #[ts(export)]
 pub enum SpeciesKind {
     Human,
     Goblin,
 }
 
-#[derive(PartialEq, Clone, Debug, Serialize, TS, Type)]
+impl SpeciesKind {
+    /// The race whose template members of the species are made from.
+    pub fn race(&self) -> CreatureRaceKind {
+        match self {
+            SpeciesKind::Human => CreatureRaceKind::Human,
+            SpeciesKind::Goblin => CreatureRaceKind::Goblin,
+        }
+    }
+
+    pub fn resistance(&self, damage_type: &DamageType) -> Resistance {
+        match (self, damage_type) {
+            // Goblin hide is thick enough to turn a blade
+            (SpeciesKind::Goblin, DamageType::Slice) => Resistance::Resistant,
+            _ => Resistance::Normal,
+        }
+    }
+}
+
+#[derive(PartialEq, Clone, Debug, Serialize, Deserialize, TS, Type)]
 #[ts(export)]
 pub enum MaterialKind {
     Stone,
+    Wood,
     Flesh,
 }
 
-#[derive(PartialEq, Clone, Debug, Serialize, TS, Type)]
+#[derive(PartialEq, Clone, Debug, Serialize, Deserialize, TS, Type)]
 #[ts(export)]
 pub struct Material {
     pub kind: MaterialKind,
@@ -38,7 +63,29 @@ pub struct Material {
     pub blocks_movement: bool,
 }
 
+impl Material {
+    pub fn resistance(&self, damage_type: &DamageType) -> Resistance {
+        self.kind.resistance(damage_type)
+    }
+}
+
 impl MaterialKind {
+    pub fn resistance(&self, damage_type: &DamageType) -> Resistance {
+        match (self, damage_type) {
+            (MaterialKind::Stone, DamageType::Slice) => Resistance::Immune,
+            (MaterialKind::Stone, DamageType::Pierce) => Resistance::Resistant,
+            (MaterialKind::Stone, DamageType::Blunt) => Resistance::Vulnerable,
+            (MaterialKind::Stone, DamageType::Fire) => Resistance::Resistant,
+            (MaterialKind::Stone, DamageType::Poison) => Resistance::Immune,
+            (MaterialKind::Wood, DamageType::Slice | DamageType::Pierce) => Resistance::Resistant,
+            (MaterialKind::Wood, DamageType::Blunt) => Resistance::Normal,
+            (MaterialKind::Wood, DamageType::Fire) => Resistance::Vulnerable,
+            (MaterialKind::Wood, DamageType::Poison) => Resistance::Immune,
+            (MaterialKind::Flesh, DamageType::Fire) => Resistance::Vulnerable,
+            (MaterialKind::Flesh, _) => Resistance::Normal,
+        }
+    }
+
     pub fn get_material(&self) -> Material {
         match self {
             MaterialKind::Stone => Material {
@@ -46,6 +93,11 @@ impl MaterialKind {
                 blocks_vision: true,
                 blocks_movement: true,
             },
+            MaterialKind::Wood => Material {
+                kind: MaterialKind::Wood,
+                blocks_vision: true,
+                blocks_movement: true,
+            },
             MaterialKind::Flesh => Material {
                 kind: MaterialKind::Flesh,
                 blocks_vision: false,
@@ -55,60 +107,84 @@ impl MaterialKind {
     }
 }
 
-#[derive(PartialEq, Clone, Debug, Serialize, TS, Type)]
+#[derive(PartialEq, Clone, Debug, Serialize, Deserialize, TS, Type)]
 #[serde(tag = "type")]
 #[ts(export)]
 pub enum EntityKind {
-    Player,
-    Npc { species: SpeciesKind },
-    Item { kind: super::equipment::ItemKind },
-    Wall { material: Material },
-    Floor { material: Material },
+    Player {
+        species: SpeciesKind,
+    },
+    Npc {
+        species: SpeciesKind,
+    },
+    Item {
+        kind: super::equipment::ItemKind,
+    },
+    Wall {
+        material: Material,
+    },
+    Floor {
+        material: Material,
+    },
+    Door {
+        material: Material,
+        open: bool,
+        /// The key needed to unlock the door, if it is locked.
+        lock: Option<KeyId>,
+    },
+    Stairs {
+        material: Material,
+        direction: StairDirection,
+    },
 }
 
-#[derive(Debug, Clone, Serialize, TS, Type)]
+#[derive(PartialEq, Eq, Clone, Copy, Debug, Serialize, Deserialize, TS, Type)]
 #[ts(export)]
+pub enum StairDirection {
+    Up,
+    Down,
+}
 
+/// Something in the world. Besides its kind, an entity is made up of optional
+/// components, so it only carries the parts that make sense for it. See
+/// [`super::components`].
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, TS, Type)]
+#[ts(export)]
 pub struct Entity {
     pub id: EntityId,
     pub kind: EntityKind,
     pub pos: Option<WorldPosition>,
-    pub stats: CoreAttributes,
-    pub status: Status,
-    pub visible: bool,
-    pub discovered: bool,
-    pub ai: Option<Ai>,
-}
-
-#[derive(Clone)]
-pub struct EntityWithoutId {
-    pub kind: EntityKind,
-    pub pos: Option<WorldPosition>,
-    pub stats: CoreAttributes,
-    pub status: Status,
+    /// Attributes, for creatures and terrain that can be worn down. `None` for
+    /// everything else, and for creatures the player can only see from outside.
+    pub stats: Option<CoreAttributes>,
+    /// Health, stamina and mana. `None` for entities that can't be hurt, such
+    /// as floors and items.
+    pub status: Option<Status>,
     pub visible: bool,
     pub discovered: bool,
     pub ai: Option<Ai>,
+    pub inventory: Option<Inventory>,
+    pub equipment: Option<Equipment>,
+    pub effects: Vec<StatusEffect>,
+    pub spells: Vec<SpellKind>,
 }
 
 impl Entity {
-    pub fn new(
-        id: EntityId,
-        kind: EntityKind,
-        pos: Option<WorldPosition>,
-        stats: CoreAttributes,
-        status: Status,
-        ai: Option<Ai>,
-    ) -> Self {
+    /// Creates an entity with no components besides its kind.
+    pub fn new(id: EntityId, kind: EntityKind) -> Self {
         Self {
             id,
             kind,
-            pos,
+            pos: None,
+            stats: None,
+            status: None,
             visible: false,
             discovered: false,
-            stats,
-            status,
-            ai,
+            ai: None,
+            inventory: None,
+            equipment: None,
+            effects: vec![],
+            spells: vec![],
         }
     }
 
@@ -117,14 +193,40 @@ impl Entity {
     }
 
     pub fn set_pos(&mut self, pos: Option<WorldPosition>) {
-        self.pos = pos.clone();
+        self.pos = pos;
     }
 
+    /// Whether the entity can step onto a spot holding `entities_in_pos`: there
+    /// has to be ground to stand on and nothing in the way.
     pub fn can_move_to(&self, entities_in_pos: Vec<Entity>) -> bool {
-        entities_in_pos.iter().all(|e| match *e.kind() {
-            EntityKind::Wall { .. } => false,
-            _ => true,
-        })
+        entities_in_pos.iter().any(|e| e.is_ground())
+            && entities_in_pos.iter().all(|e| !e.blocks_movement())
+    }
+
+    /// Whether something can stand on the entity: floors, stairs and doorways.
+    pub fn is_ground(&self) -> bool {
+        matches!(
+            self.kind,
+            EntityKind::Floor { .. } | EntityKind::Stairs { .. } | EntityKind::Door { .. }
+        )
+    }
+
+    pub fn blocks_movement(&self) -> bool {
+        match &self.kind {
+            EntityKind::Wall { material } | EntityKind::Door { material, .. } => {
+                material.blocks_movement
+            }
+            _ => false,
+        }
+    }
+
+    pub fn blocks_vision(&self) -> bool {
+        match &self.kind {
+            EntityKind::Wall { material } | EntityKind::Door { material, .. } => {
+                material.blocks_vision
+            }
+            _ => false,
+        }
     }
 
     pub fn get_pos_in_direction(&self, dir: Direction) -> Option<WorldPosition> {
@@ -149,6 +251,30 @@ impl Entity {
         }
     }
 
+    /// Speed after effects and penalties from exhaustion.
+    pub fn effective_speed(&self) -> u64 {
+        let exhaustion = self
+            .status
+            .as_ref()
+            .map_or(0, |status| status.exhaustion.speed_modifier());
+        self.effective_stats()
+            .speed
+            .saturating_add_signed(exhaustion)
+    }
+
+    /// What can be told about the entity just by looking at it. Its health and
+    /// afflictions show, but not its attributes, belongings, spells or thoughts.
+    pub fn outward(&self) -> Entity {
+        Entity {
+            stats: None,
+            ai: None,
+            inventory: None,
+            equipment: None,
+            spells: Vec::new(),
+            ..self.clone()
+        }
+    }
+
     pub fn ai(&self) -> Option<&Ai> {
         self.ai.as_ref()
     }
@@ -156,37 +282,64 @@ impl Entity {
     pub fn kind(&self) -> &EntityKind {
         &self.kind
     }
-}
 
-impl EntityWithoutId {
-    pub fn new(
-        kind: EntityKind,
-        pos: Option<WorldPosition>,
-        stats: CoreAttributes,
-        status: Status,
-        ai: Option<Ai>,
-    ) -> Self {
-        Self {
-            kind,
-            pos,
-            visible: false,
-            discovered: false,
-            stats,
-            status,
-            ai,
+    /// What the entity is made of. Creatures are all flesh.
+    pub fn material(&self) -> Option<Material> {
+        match &self.kind {
+            EntityKind::Wall { material }
+            | EntityKind::Floor { material }
+            | EntityKind::Door { material, .. }
+            | EntityKind::Stairs { material, .. } => Some(material.clone()),
+            EntityKind::Player { .. } | EntityKind::Npc { .. } => {
+                Some(MaterialKind::Flesh.get_material())
+            }
+            EntityKind::Item { .. } => None,
         }
     }
 
-    pub fn with_id(self, id: EntityId) -> Entity {
-        Entity {
-            id,
-            kind: self.kind,
-            pos: self.pos,
-            stats: self.stats,
-            status: self.status,
-            visible: self.visible,
-            discovered: self.discovered,
-            ai: self.ai,
-        }
+    /// Percentage of damage of the given type that gets through, combining the
+    /// resistances of the entity's material and species.
+    pub fn damage_multiplier(&self, damage_type: &DamageType) -> u64 {
+        let material = self
+            .material()
+            .map_or(Resistance::Normal, |m| m.resistance(damage_type));
+        let species = match &self.kind {
+            EntityKind::Player { species } | EntityKind::Npc { species } => {
+                species.resistance(damage_type)
+            }
+            _ => Resistance::Normal,
+        };
+        material.multiplier() * species.multiplier() / 100
+    }
+
+    pub fn knows_spell(&self, spell: SpellKind) -> bool {
+        self.spells.contains(&spell)
+    }
+
+    pub fn is_creature(&self) -> bool {
+        matches!(
+            self.kind,
+            EntityKind::Player { .. } | EntityKind::Npc { .. }
+        )
+    }
+
+    pub fn is_item(&self) -> bool {
+        matches!(self.kind, EntityKind::Item { .. })
+    }
+
+    pub fn inventory(&self) -> Option<&Inventory> {
+        self.inventory.as_ref()
+    }
+
+    pub fn inventory_mut(&mut self) -> Option<&mut Inventory> {
+        self.inventory.as_mut()
+    }
+
+    pub fn equipment(&self) -> Option<&Equipment> {
+        self.equipment.as_ref()
+    }
+
+    pub fn equipment_mut(&mut self) -> Option<&mut Equipment> {
+        self.equipment.as_mut()
     }
 }
@@ -1,3 +1,5 @@
+pub mod dungeon;
 pub mod entity;
+pub mod projectile;
 pub mod world;
 pub mod world_position;
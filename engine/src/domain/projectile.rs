@@ -0,0 +1,72 @@
+use serde::Serialize;
+use specta::Type;
+use ts_rs::TS;
+
+use super::{entity::EntityId, world::World, world_position::WorldPosition};
+
+/// The route a projectile takes and what it runs into.
+#[derive(Debug, Clone, PartialEq, Serialize, TS, Type)]
+#[ts(export)]
+pub struct ProjectilePath {
+    /// Every tile the projectile passes through, not counting where it was launched from.
+    pub path: Vec<WorldPosition>,
+    /// The creature or obstacle that stopped the projectile, if any.
+    pub hit: Option<EntityId>,
+    /// Where the projectile comes to rest.
+    pub end: WorldPosition,
+}
+
+impl World {
+    /// Traces a projectile from `from` towards `target` along a Bresenham line,
+    /// going no further than `range`. It stops at the first creature it meets,
+    /// or just short of the first tile that blocks movement.
+    pub fn trace_projectile(
+        &self,
+        from: &WorldPosition,
+        target: &WorldPosition,
+        range: i32,
+    ) -> ProjectilePath {
+        let mut path = vec![];
+        let mut end = *from;
+
+        for pos in self.get_line(from, target).into_iter().skip(1) {
+            if from.distance_to(&pos) > range as f32 {
+                break;
+            }
+
+            if self.blocks_movement(&pos) {
+                let obstacle = self
+                    .get_entities_by_pos(&pos)
+                    .into_iter()
+                    .find(|e| e.blocks_movement())
+                    .map(|e| e.id);
+                return ProjectilePath {
+                    path,
+                    hit: obstacle,
+                    end,
+                };
+            }
+
+            path.push(pos);
+            end = pos;
+
+            let creature = self
+                .get_entities_by_pos(&pos)
+                .into_iter()
+                .find(|e| e.is_creature());
+            if let Some(creature) = creature {
+                return ProjectilePath {
+                    path,
+                    hit: Some(creature.id),
+                    end,
+                };
+            }
+        }
+
+        ProjectilePath {
+            path,
+            hit: None,
+            end,
+        }
+    }
+}
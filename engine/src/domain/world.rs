@@ -1,29 +1,48 @@
-use std::collections::{HashMap, HashSet};
+use std::collections::{BTreeMap, HashMap, HashSet, VecDeque};
 
-use serde::Serialize;
+use serde::{Deserialize, Serialize};
 use specta::Type;
 use ts_rs::TS;
 
 use super::{
-    entity::{Entity, EntityId, EntityKind},
+    dungeon::{Depth, Level},
+    entity::{components::Component, Entity, EntityBuilder, EntityId},
     world_position::WorldPosition,
 };
 
-#[derive(Debug, Clone, Serialize, TS, Type)]
+/// How far the player can see.
+pub const SIGHT_RADIUS: i32 = 8;
+/// Most tiles a path search will look at before giving up.
+const MAX_PATH_SEARCH: usize = 4000;
+
+#[derive(Debug, Clone, Serialize, Deserialize, TS, Type)]
 #[ts(export)]
 pub struct World {
-    pub entities: HashMap<EntityId, Entity>,
+    pub entities: BTreeMap<EntityId, Entity>,
     pub next_entity_id: EntityId,
     pub player_id: EntityId,
+    /// How far down the current level is.
+    pub depth: Depth,
+    /// Levels that were visited and left, by depth.
+    pub levels: BTreeMap<Depth, Level>,
+    /// Terrain on the current level as the player last saw it, by id.
+    #[serde(default)]
+    pub remembered: BTreeMap<EntityId, Entity>,
 }
 
 impl World {
-    pub fn new(player_id: EntityId) -> Self {
-        World {
-            entities: HashMap::new(),
-            player_id,
-            next_entity_id: player_id + 1,
-        }
+    /// Creates a world holding nothing but the player.
+    pub fn new(player: EntityBuilder) -> Self {
+        let mut world = World {
+            entities: BTreeMap::new(),
+            player_id: 0,
+            next_entity_id: 1,
+            depth: 0,
+            levels: BTreeMap::new(),
+            remembered: BTreeMap::new(),
+        };
+        world.player_id = player.spawn(&mut world);
+        world
     }
 
     pub fn get_next_entity_id(&mut self) -> EntityId {
@@ -55,11 +74,52 @@ impl World {
     pub fn get_entities_by_pos(&self, pos: &WorldPosition) -> Vec<&Entity> {
         self.entities
             .values()
-            .into_iter()
             .filter(|e| e.pos().is_some_and(|entity_pos| entity_pos == *pos))
             .collect()
     }
 
+    pub fn get_items_by_pos(&self, pos: &WorldPosition) -> Vec<&Entity> {
+        self.get_entities_by_pos(pos)
+            .into_iter()
+            .filter(|e| e.is_item())
+            .collect()
+    }
+
+    /// Gets the entities held in an entity's inventory, in inventory order.
+    pub fn get_inventory_items(&self, entity_id: EntityId) -> Vec<&Entity> {
+        self.get_entity(entity_id)
+            .and_then(|e| e.inventory())
+            .map(|inventory| {
+                inventory
+                    .items
+                    .iter()
+                    .filter_map(|item_id| self.get_entity(*item_id))
+                    .collect()
+            })
+            .unwrap_or_default()
+    }
+
+    /// Gets the items an entity has equipped.
+    pub fn get_equipped_items(&self, entity_id: EntityId) -> Vec<&Entity> {
+        self.get_entity(entity_id)
+            .and_then(|e| e.equipment())
+            .map(|equipment| {
+                equipment
+                    .equipped_items()
+                    .into_iter()
+                    .filter_map(|item_id| self.get_entity(item_id))
+                    .collect()
+            })
+            .unwrap_or_default()
+    }
+
+    /// Gets every entity that has the component `C`, along with the component.
+    pub fn query<C: Component>(&self) -> impl Iterator<Item = (&Entity, &C)> {
+        self.entities
+            .values()
+            .filter_map(|e| e.get::<C>().map(|component| (e, component)))
+    }
+
     pub fn get_entity_by_id_mut(&mut self, entity_id: &EntityId) -> Option<&mut Entity> {
         self.entities.get_mut(entity_id)
     }
@@ -67,12 +127,15 @@ impl World {
     pub fn get_entities_by_pos_mut(&mut self, pos: &WorldPosition) -> Vec<&mut Entity> {
         self.entities
             .values_mut()
-            .into_iter()
             .filter(|e| e.pos().is_some_and(|entity_pos| entity_pos == *pos))
             .collect()
     }
 
-    fn get_visible_positions(&self, from: &WorldPosition, radius: i32) -> HashSet<WorldPosition> {
+    pub fn get_visible_positions(
+        &self,
+        from: &WorldPosition,
+        radius: i32,
+    ) -> HashSet<WorldPosition> {
         let mut visible = HashSet::new();
 
         // Check every position within a square of size radius*2+1
@@ -88,7 +151,7 @@ impl World {
                     continue;
                 }
 
-                if self.has_line_of_sight(&from, &to) {
+                if self.has_line_of_sight(from, &to) {
                     visible.insert(to);
                 }
             }
@@ -97,8 +160,101 @@ impl World {
         visible
     }
 
+    /// Whether anything at `pos` blocks vision.
+    pub fn blocks_vision(&self, pos: &WorldPosition) -> bool {
+        self.get_entities_by_pos(pos)
+            .iter()
+            .any(|e| e.blocks_vision())
+    }
+
+    /// Whether anything at `pos` blocks movement.
+    pub fn blocks_movement(&self, pos: &WorldPosition) -> bool {
+        self.get_entities_by_pos(pos)
+            .iter()
+            .any(|e| e.blocks_movement())
+    }
+
+    /// Whether `pos` holds a closed door that isn't locked.
+    pub fn has_openable_door(&self, pos: &WorldPosition) -> bool {
+        self.get_entities_by_pos(pos)
+            .iter()
+            .any(|e| e.is_closed_door() && e.door_lock().is_none())
+    }
+
+    /// The positions the player can see from where they stand.
+    pub fn player_visible_positions(&self) -> HashSet<WorldPosition> {
+        self.get_entity(self.player_id)
+            .and_then(|player| player.pos())
+            .map(|pos| self.get_visible_positions(&pos, SIGHT_RADIUS))
+            .unwrap_or_default()
+    }
+
+    /// Recalculates what the player can see, marking everything in view as
+    /// visible and discovered.
+    pub fn update_visibility(&mut self) {
+        let _span = tracing::trace_span!("update_visibility").entered();
+        let visible_positions = self.player_visible_positions();
+
+        for entity in self.entities.values_mut() {
+            entity.visible = entity
+                .pos()
+                .is_some_and(|pos| visible_positions.contains(&pos));
+            entity.discovered |= entity.visible;
+            if entity.visible && !entity.is_creature() && !entity.is_item() {
+                self.remembered.insert(entity.id, entity.outward());
+            }
+        }
+        tracing::trace!(visible = visible_positions.len(), "field of view updated");
+    }
+
+    /// Finds the shortest walkable path from `from` to `to`, not including `from`.
+    /// Closed doors count as walkable when `can_open_doors` is set.
+    pub fn find_path(
+        &self,
+        from: &WorldPosition,
+        to: &WorldPosition,
+        can_open_doors: bool,
+    ) -> Option<Vec<WorldPosition>> {
+        let mut came_from: HashMap<WorldPosition, WorldPosition> = HashMap::new();
+        let mut queue = VecDeque::from([*from]);
+        let mut visited = HashSet::from([*from]);
+
+        while let Some(pos) = queue.pop_front() {
+            if pos == *to {
+                let mut path = vec![pos];
+                let mut current = pos;
+                while let Some(previous) = came_from.get(&current) {
+                    if previous == from {
+                        break;
+                    }
+                    path.push(*previous);
+                    current = *previous;
+                }
+                path.reverse();
+                tracing::trace!(%from, %to, searched = visited.len(), length = path.len(), "path found");
+                return Some(path);
+            }
+            if visited.len() > MAX_PATH_SEARCH {
+                tracing::debug!(%from, %to, searched = visited.len(), "path search gave up");
+                return None;
+            }
+
+            for neighbor in pos.neighbors() {
+                let walkable = neighbor == *to
+                    || !self.blocks_movement(&neighbor)
+                    || (can_open_doors && self.has_openable_door(&neighbor));
+                if walkable && visited.insert(neighbor) {
+                    came_from.insert(neighbor, pos);
+                    queue.push_back(neighbor);
+                }
+            }
+        }
+        tracing::trace!(%from, %to, searched = visited.len(), "no path");
+        None
+    }
+
     // Check if there's a clear line of sight between two positions
-    fn has_line_of_sight(&self, from: &WorldPosition, to: &WorldPosition) -> bool {
+    pub fn has_line_of_sight(&self, from: &WorldPosition, to: &WorldPosition) -> bool {
         // Always see your own position
         if from == to {
             return true;
@@ -108,10 +264,7 @@ impl World {
 
         // Check each position along the line except the start and end
         for pos in line.iter().skip(1).take(line.len() - 2) {
-            let entities_in_pos = self.get_entities_by_pos(pos);
-            if entities_in_pos.iter().any(
-                |e| matches!(&e.kind(), EntityKind::Wall { material } if material.blocks_vision),
-            ) {
+            if self.blocks_vision(pos) {
                 return false;
             }
         }
@@ -120,7 +273,7 @@ impl World {
     }
 
     // Bresenham's line algorithm
-    fn get_line(&self, from: &WorldPosition, to: &WorldPosition) -> Vec<WorldPosition> {
+    pub fn get_line(&self, from: &WorldPosition, to: &WorldPosition) -> Vec<WorldPosition> {
         let mut line = Vec::new();
 
         let mut x = from.x;
@@ -3,7 +3,9 @@ use std::fmt::Display;
 use specta::Type;
 use ts_rs::TS;
 
-#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug, serde::Serialize, TS, Type)]
+#[derive(
+    Clone, Copy, PartialEq, Eq, Hash, Debug, serde::Serialize, serde::Deserialize, TS, Type,
+)]
 #[ts(export)]
 pub struct WorldPosition {
     pub x: i32,
@@ -25,6 +27,12 @@ impl WorldPosition {
         (self.x - other.x).abs() + (self.y - other.y).abs()
     }
 
+    /// Distance counting diagonal steps as one, so every position around this
+    /// one is at distance 1.
+    pub fn chebyshev_distance(&self, other: &WorldPosition) -> i32 {
+        (self.x - other.x).abs().max((self.y - other.y).abs())
+    }
+
     pub fn neighbors(&self) -> Vec<WorldPosition> {
         vec![
             WorldPosition::new(self.x + 1, self.y),
@@ -33,6 +41,15 @@ impl WorldPosition {
             WorldPosition::new(self.x, self.y - 1),
         ]
     }
+
+    /// The eight positions around this one, diagonals included.
+    pub fn surrounding(&self) -> Vec<WorldPosition> {
+        (-1..=1)
+            .flat_map(|dy| (-1..=1).map(move |dx| (dx, dy)))
+            .filter(|&offset| offset != (0, 0))
+            .map(|(dx, dy)| WorldPosition::new(self.x + dx, self.y + dy))
+            .collect()
+    }
 }
 
 impl From<WorldPosition> for (f32, f32) {
@@ -5,47 +5,47 @@ pub mod debug_data;
 pub mod domain;
 pub mod prefabs;
 
-use application::{events::GameEvent, game_loop::GameState, turns::TurnManager};
-use domain::{
-    entity::{CoreAttributes, Entity, EntityId, EntityKind, Exhaustion, Status},
-    world::World,
-    world_position::WorldPosition,
+use application::{
+    errors::EngineResult, game_loop::GameState, new_game::NewGameConfig, turns::TurnManager,
 };
+use core::rng;
+use domain::{world::World, world_position::WorldPosition};
 
-pub fn create_initial_game_state() -> GameState {
-    let player_id = 1;
-    let mut world = World::new(player_id);
+/// Starts a new game set up the way `config` asks. The same config, seed
+/// included, always gives the same game.
+pub fn create_game_state(config: &NewGameConfig) -> EngineResult<GameState> {
+    config.validate()?;
+    let seed = config.seed.unwrap_or_else(rng::random_seed);
+    let player = config
+        .create_player(&mut rng::character(seed))
+        .at(WorldPosition::new(0, 0));
 
-    // Add player
-    world.add_entity(Entity::new(
-        player_id,
-        EntityKind::Player,
-        Some(WorldPosition::new(0, 0)),
-        CoreAttributes::default(),
-        Status {
-            health: 10,
-            stamina: 10,
-            mana: 10,
-            exhaustion: Exhaustion::Rested,
-        },
-        None,
-    ));
+    let world = World::new(player);
+    let player_id = world.player_id;
+    let mut turn_manager = TurnManager::new();
+    turn_manager.initialize(player_id, &[]);
+    let mut game_state = GameState::new(world, turn_manager, seed);
+    config.give_kit(&mut game_state, player_id)?;
 
     // Add goblin
-    let goblin_id = world.get_next_entity_id();
-    world.add_entity(
-        prefabs::goblins::create_goblin(Some(WorldPosition::new(5, 0))).with_id(goblin_id),
-    );
+    let goblin = prefabs::goblins::create_goblin(Some(WorldPosition::new(5, 0)));
+    game_state.spawn(goblin);
+
+    // Add some loot next to the player
+    let loot = [
+        prefabs::items::create_leather_armor(Some(WorldPosition::new(1, 1))),
+        prefabs::items::create_dagger(Some(WorldPosition::new(1, -1))),
+        prefabs::items::create_shortbow(Some(WorldPosition::new(2, 2))),
+    ];
+    for item in loot {
+        game_state.spawn(item);
+    }
 
     // Add level
     for entity in debug_data::basic_level::get_level() {
-        let id = world.get_next_entity_id();
-        world.add_entity(entity.with_id(id));
+        game_state.spawn(entity);
     }
 
-    // Set up turn manager
-    let mut turn_manager = TurnManager::new();
-    turn_manager.initialize(player_id, &[goblin_id]);
-
-    GameState::new(world, turn_manager)
+    game_state.start();
+    Ok(game_state)
 }
@@ -1,7 +1,7 @@
 use crate::domain::{
     entity::{
-        types::EntityWithoutId, CoreAttributes, Entity, EntityId, EntityKind, Exhaustion, Material,
-        MaterialKind, SpeciesKind, Status,
+        builder::EntityBuilder, CoreAttributes, EntityKind, Exhaustion, KeyId, Material,
+        MaterialKind, Pool, Regeneration, StairDirection, Status,
     },
     world_position::WorldPosition,
 };
@@ -14,52 +14,51 @@ fn get_stone_material() -> Material {
     }
 }
 
-pub fn create_stone_wall(pos: Option<WorldPosition>) -> EntityWithoutId {
-    EntityWithoutId {
-        kind: EntityKind::Wall {
-            material: get_stone_material(),
-        },
-        pos,
-        status: Status {
-            health: 5,
-            stamina: 0,
-            mana: 0,
-            exhaustion: Exhaustion::Rested,
-        },
-        stats: CoreAttributes {
+/// Terrain only needs durability and health, to be worn down.
+fn with_terrain_durability(builder: EntityBuilder, durability: u64, health: u64) -> EntityBuilder {
+    builder
+        .stats(CoreAttributes {
             strength: 0,
             speed: 0,
-            durability: 10,
+            durability,
             fortitude: 0,
             magic: 0,
-        },
-        visible: true,
-        discovered: false,
-        ai: None,
-    }
+        })
+        .status(Status {
+            health: Pool::new(health),
+            stamina: Pool::new(0),
+            mana: Pool::new(0),
+            exhaustion: Exhaustion::Rested,
+            fatigue: 0,
+            regeneration: Regeneration::default(),
+        })
 }
 
-pub fn create_stone_floor(pos: Option<WorldPosition>) -> EntityWithoutId {
-    EntityWithoutId {
-        kind: EntityKind::Floor {
-            material: get_stone_material(),
-        },
-        pos,
-        status: Status {
-            health: 5,
-            stamina: 0,
-            mana: 0,
-            exhaustion: Exhaustion::Rested,
-        },
-        stats: CoreAttributes {
-            strength: 0,
-            speed: 0,
-            durability: 10,
-            fortitude: 0,
-            magic: 0,
-        },
-        visible: true,
-        discovered: false,
-        ai: None,
-    }
+pub fn create_stone_wall(pos: Option<WorldPosition>) -> EntityBuilder {
+    with_terrain_durability(EntityBuilder::wall(get_stone_material()).at(pos), 10, 5)
+}
+
+pub fn create_stone_floor(pos: Option<WorldPosition>) -> EntityBuilder {
+    EntityBuilder::floor(get_stone_material()).at(pos)
+}
+
+/// Creates a closed wooden door, locked with `lock` if given.
+pub fn create_wooden_door(pos: Option<WorldPosition>, lock: Option<KeyId>) -> EntityBuilder {
+    let door = EntityBuilder::new(EntityKind::Door {
+        material: MaterialKind::Wood.get_material(),
+        open: false,
+        lock,
+    })
+    .at(pos);
+    with_terrain_durability(door, 5, 3)
+}
+
+/// Creates stone stairs leading to the level above or below.
+pub fn create_stone_stairs(pos: Option<WorldPosition>, direction: StairDirection) -> EntityBuilder {
+    let material = Material {
+        kind: MaterialKind::Stone,
+        blocks_vision: false,
+        blocks_movement: false,
+    };
+    EntityBuilder::stairs(material, direction).at(pos)
 }
@@ -1,32 +1,23 @@
 use crate::domain::{
     entity::{
-        ai::Ai, types::EntityWithoutId, CoreAttributes, Entity, EntityId, EntityKind, Exhaustion,
-        SpeciesKind, Stats, Status,
+        ai::Ai, builder::EntityBuilder, CoreAttributes, Equipment, Inventory, SpeciesKind, Status,
     },
     world_position::WorldPosition,
 };
 
-pub fn create_goblin(pos: Option<WorldPosition>) -> EntityWithoutId {
-    EntityWithoutId {
-        kind: EntityKind::Npc {
-            species: SpeciesKind::Goblin,
-        },
-        pos,
-        status: Status {
-            health: 2,
-            stamina: 2,
-            mana: 0,
-            exhaustion: Exhaustion::Rested,
-        },
-        stats: CoreAttributes {
-            strength: 4,
-            speed: 5,
-            durability: 3,
-            fortitude: 2,
-            magic: 0,
-        },
-        visible: true,
-        discovered: false,
-        ai: Some(Ai::new()),
-    }
+pub fn create_goblin(pos: Option<WorldPosition>) -> EntityBuilder {
+    let stats = CoreAttributes {
+        strength: 4,
+        speed: 5,
+        durability: 3,
+        fortitude: 2,
+        magic: 0,
+    };
+    EntityBuilder::npc(SpeciesKind::Goblin)
+        .at(pos)
+        .status(Status::from_attributes(&stats))
+        .stats(stats)
+        .ai(Ai::new())
+        .inventory(Inventory::new())
+        .equipment(Equipment::new())
 }
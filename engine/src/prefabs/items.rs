@@ -0,0 +1,61 @@
+use crate::{
+    core::dice::{Dice, DieRoll},
+    domain::{
+        entity::{builder::EntityBuilder, Damage, DamageType, ItemKind, KeyId},
+        world_position::WorldPosition,
+    },
+};
+
+fn create_item(kind: ItemKind, pos: Option<WorldPosition>) -> EntityBuilder {
+    EntityBuilder::item(kind).at(pos)
+}
+
+pub fn create_leather_armor(pos: Option<WorldPosition>) -> EntityBuilder {
+    create_item(ItemKind::Armor { defense: 1 }, pos)
+}
+
+pub fn create_dagger(pos: Option<WorldPosition>) -> EntityBuilder {
+    create_item(
+        ItemKind::Weapon {
+            damage: vec![Damage {
+                damage_type: DamageType::Pierce,
+                damage: DieRoll::new(1, Dice::D4, 0),
+            }],
+            two_handed: false,
+            range: None,
+        },
+        pos,
+    )
+}
+
+pub fn create_warhammer(pos: Option<WorldPosition>) -> EntityBuilder {
+    create_item(
+        ItemKind::Weapon {
+            damage: vec![Damage {
+                damage_type: DamageType::Blunt,
+                damage: DieRoll::new(1, Dice::D10, 0),
+            }],
+            two_handed: true,
+            range: None,
+        },
+        pos,
+    )
+}
+
+pub fn create_shortbow(pos: Option<WorldPosition>) -> EntityBuilder {
+    create_item(
+        ItemKind::Weapon {
+            damage: vec![Damage {
+                damage_type: DamageType::Pierce,
+                damage: DieRoll::new(1, Dice::D6, 0),
+            }],
+            two_handed: true,
+            range: Some(8),
+        },
+        pos,
+    )
+}
+
+pub fn create_key(pos: Option<WorldPosition>, key_id: KeyId) -> EntityBuilder {
+    create_item(ItemKind::Key { key_id }, pos)
+}
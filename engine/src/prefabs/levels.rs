@@ -0,0 +1,69 @@
+use rand::Rng;
+
+use crate::{
+    domain::{
+        dungeon::Depth,
+        entity::{builder::EntityBuilder, StairDirection},
+        world_position::WorldPosition,
+    },
+    prefabs,
+};
+
+/// Generates a walled room for the given depth with stairs back up, stairs
+/// further down and a few more goblins the deeper it is.
+pub fn create_level<R: Rng + ?Sized>(depth: Depth, rng: &mut R) -> Vec<EntityBuilder> {
+    let width = rng.random_range(8..=14);
+    let height = rng.random_range(6..=10);
+    let mut level = vec![];
+
+    // Walls around the room
+    for x in -1..=width {
+        level.push(prefabs::dungeon_environment::create_stone_wall(Some(
+            WorldPosition::new(x, -1),
+        )));
+        level.push(prefabs::dungeon_environment::create_stone_wall(Some(
+            WorldPosition::new(x, height),
+        )));
+    }
+    for y in 0..height {
+        level.push(prefabs::dungeon_environment::create_stone_wall(Some(
+            WorldPosition::new(-1, y),
+        )));
+        level.push(prefabs::dungeon_environment::create_stone_wall(Some(
+            WorldPosition::new(width, y),
+        )));
+    }
+
+    // Floor across the whole room
+    for x in 0..width {
+        for y in 0..height {
+            level.push(prefabs::dungeon_environment::create_stone_floor(Some(
+                WorldPosition::new(x, y),
+            )));
+        }
+    }
+
+    // Everything else goes on its own free tile inside the room
+    let mut taken = vec![];
+    let mut free_position = |rng: &mut R| loop {
+        let pos = WorldPosition::new(rng.random_range(0..width), rng.random_range(0..height));
+        if !taken.contains(&pos) {
+            taken.push(pos);
+            return pos;
+        }
+    };
+
+    level.push(prefabs::dungeon_environment::create_stone_stairs(
+        Some(free_position(rng)),
+        StairDirection::Up,
+    ));
+    level.push(prefabs::dungeon_environment::create_stone_stairs(
+        Some(free_position(rng)),
+        StairDirection::Down,
+    ));
+    for _ in 0..=depth.min(4) {
+        level.push(prefabs::goblins::create_goblin(Some(free_position(rng))));
+    }
+
+    level
+}
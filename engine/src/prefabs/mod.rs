@@ -1,2 +1,4 @@
 pub mod dungeon_environment;
 pub mod goblins;
+pub mod items;
+pub mod levels;
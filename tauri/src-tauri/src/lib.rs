@@ -1,60 +1,378 @@
 use engine::{
-    application::{events::GameEvent, game_loop::GameState},
-    core::types::Direction,
-    create_initial_game_state,
-    domain::{world::World, world_position::WorldPosition},
+    application::{
+        errors::EngineError,
+        events::GameEvent,
+        game_loop::GameState,
+        messages::Message,
+        new_game::{CharacterOptions, NewGameConfig},
+        sync::{WorldDiff, WorldSnapshot, WorldSync},
+    },
+    core::{logging::init_tracing, types::Direction},
+    create_game_state,
+    domain::{
+        entity::{Entity, EntityId, Hand, SpellKind},
+        projectile::ProjectilePath,
+        world_position::WorldPosition,
+    },
 };
 use serde::Serialize;
 use specta::Type;
 use specta_typescript::Typescript;
 use std::sync::Mutex;
-use tauri::State;
-use tauri_specta::collect_commands;
-use ts_rs::TS;
+use tauri::{AppHandle, Manager, State};
+use tauri_specta::{collect_commands, collect_events, Event};
+/// Where the game is saved, in the app's data directory.
+const SAVE_FILE: &str = "savegame.json";
 
-#[derive(Serialize, TS, Type)]
-#[ts(export)]
-struct ClientGameState {
-    pub world: World,
+/// Sent after every player action with whatever changed in the world.
+#[derive(Clone, Serialize, Type, Event)]
+struct WorldUpdated(WorldDiff);
+
+/// The game, along with what the frontend has been told about it so far.
+struct Game {
+    state: GameState,
+    sync: WorldSync,
 }
 
-impl From<World> for ClientGameState {
-    fn from(state: World) -> ClientGameState {
-        ClientGameState { world: state }
+type GameStateWrapper<'a> = State<'a, Mutex<Game>>;
+
+/// Carries out an action for the player and pushes what it changed to the frontend.
+fn act(
+    app: &AppHandle,
+    state: &GameStateWrapper,
+    event: impl FnOnce(EntityId) -> GameEvent,
+) -> Result<(), EngineError> {
+    if let Ok(mut game) = state.lock() {
+        let player_id = game.state.world.player_id;
+
+        game.state.process_player_action(event(player_id))?;
+        if let Some(diff) = game.sync.diff(&game.state.world) {
+            // A diff that doesn't arrive shows up as a gap in the sequence,
+            // which makes the frontend ask for a snapshot.
+            if let Err(error) = WorldUpdated(diff).emit(app) {
+                tracing::warn!(%error, "failed to send world update");
+            }
+        }
+        Ok(())
+    } else {
+        Err(EngineError::StateUnavailable)
     }
 }
 
-type GameStateWrapper<'a> = State<'a, Mutex<GameState>>;
+/// Sends the player's whole view. Used when the frontend loads or has missed an update.
+#[specta::specta]
+#[tauri::command]
+fn get_game_state(state: GameStateWrapper) -> Result<WorldSnapshot, EngineError> {
+    if let Ok(mut game) = state.lock() {
+        let game = &mut *game;
+        Ok(game.sync.snapshot(&game.state.world))
+    } else {
+        Err(EngineError::StateUnavailable)
+    }
+}
+
+/// The species, kits and difficulties to choose from on the character creation screen.
+#[specta::specta]
+#[tauri::command]
+fn get_character_options() -> CharacterOptions {
+    CharacterOptions::default()
+}
+
+/// Checks the choices made so far, so the character creation screen can point
+/// out what's wrong before a game is started.
+#[specta::specta]
+#[tauri::command]
+fn validate_character(config: NewGameConfig) -> Result<(), EngineError> {
+    config.validate()
+}
+
+/// Throws away the current game and starts a new one from the choices made
+/// for it. A seed is picked if none is given.
+#[specta::specta]
+#[tauri::command]
+fn new_game(state: GameStateWrapper, config: NewGameConfig) -> Result<WorldSnapshot, EngineError> {
+    let new_state = create_game_state(&config)?;
+    tracing::info!(seed = new_state.seed, "starting a new game");
+    replace_game(&state, new_state)
+}
+
+#[specta::specta]
+#[tauri::command]
+fn save_game(app: AppHandle, state: GameStateWrapper) -> Result<(), EngineError> {
+    let json = if let Ok(game) = state.lock() {
+        game.state.save()?
+    } else {
+        return Err(EngineError::StateUnavailable);
+    };
+
+    let dir = app
+        .path()
+        .app_data_dir()
+        .map_err(EngineError::save_failed)?;
+    std::fs::create_dir_all(&dir).map_err(EngineError::save_failed)?;
+    std::fs::write(dir.join(SAVE_FILE), json).map_err(EngineError::save_failed)
+}
 
+/// Replaces the current game with the saved one.
 #[specta::specta]
 #[tauri::command]
-fn get_game_state(state: GameStateWrapper) -> Result<ClientGameState, String> {
-    if let Ok(game_state) = state.lock() {
-        Ok(ClientGameState::from(game_state.world.clone()))
+fn load_game(app: AppHandle, state: GameStateWrapper) -> Result<WorldSnapshot, EngineError> {
+    let path = app
+        .path()
+        .app_data_dir()
+        .map_err(EngineError::invalid_save)?
+        .join(SAVE_FILE);
+    let json = std::fs::read_to_string(path).map_err(EngineError::invalid_save)?;
+    replace_game(&state, GameState::load(&json)?)
+}
+
+/// Swaps in another game and sends the frontend all of it.
+fn replace_game(
+    state: &GameStateWrapper,
+    new_state: GameState,
+) -> Result<WorldSnapshot, EngineError> {
+    if let Ok(mut game) = state.lock() {
+        game.state = new_state;
+        game.sync.forget();
+        let game = &mut *game;
+        Ok(game.sync.snapshot(&game.state.world))
     } else {
-        Err("Error fetching game state from back end".to_string())
+        Err(EngineError::StateUnavailable)
     }
 }
 
 #[specta::specta]
 #[tauri::command]
-fn move_player(state: GameStateWrapper, direction: Direction) -> Result<ClientGameState, String> {
-    if let Ok(mut game_state) = state.lock() {
-        let player_id = game_state.world.player_id;
+fn move_player(
+    app: AppHandle,
+    state: GameStateWrapper,
+    direction: Direction,
+) -> Result<(), EngineError> {
+    act(&app, &state, |player_id| {
+        GameEvent::MoveByDirection(player_id, direction)
+    })
+}
+
+#[specta::specta]
+#[tauri::command]
+fn attack(
+    app: AppHandle,
+    state: GameStateWrapper,
+    direction: Direction,
+) -> Result<(), EngineError> {
+    act(&app, &state, |player_id| {
+        GameEvent::Attack(player_id, direction)
+    })
+}
+
+#[specta::specta]
+#[tauri::command]
+fn open_door(
+    app: AppHandle,
+    state: GameStateWrapper,
+    direction: Direction,
+) -> Result<(), EngineError> {
+    act(&app, &state, |player_id| {
+        GameEvent::OpenDoor(player_id, direction)
+    })
+}
+
+#[specta::specta]
+#[tauri::command]
+fn close_door(
+    app: AppHandle,
+    state: GameStateWrapper,
+    direction: Direction,
+) -> Result<(), EngineError> {
+    act(&app, &state, |player_id| {
+        GameEvent::CloseDoor(player_id, direction)
+    })
+}
+
+#[specta::specta]
+#[tauri::command]
+fn rest(app: AppHandle, state: GameStateWrapper) -> Result<(), EngineError> {
+    act(&app, &state, GameEvent::Rest)
+}
 
-        game_state.handle_event(GameEvent::MoveByDirection(player_id, direction));
-        Ok(ClientGameState::from(game_state.world.clone()))
+#[specta::specta]
+#[tauri::command]
+fn pick_up(app: AppHandle, state: GameStateWrapper, item_id: EntityId) -> Result<(), EngineError> {
+    act(&app, &state, |player_id| {
+        GameEvent::PickUp(player_id, item_id)
+    })
+}
+
+#[specta::specta]
+#[tauri::command]
+fn drop_item(
+    app: AppHandle,
+    state: GameStateWrapper,
+    item_id: EntityId,
+) -> Result<(), EngineError> {
+    act(&app, &state, |player_id| {
+        GameEvent::DropItem(player_id, item_id)
+    })
+}
+
+/// Equips a carried item. Weapons go in `hand` if one is given.
+#[specta::specta]
+#[tauri::command]
+fn equip(
+    app: AppHandle,
+    state: GameStateWrapper,
+    item_id: EntityId,
+    hand: Option<Hand>,
+) -> Result<(), EngineError> {
+    act(&app, &state, |player_id| {
+        GameEvent::Equip(player_id, item_id, hand)
+    })
+}
+
+#[specta::specta]
+#[tauri::command]
+fn unequip(app: AppHandle, state: GameStateWrapper, item_id: EntityId) -> Result<(), EngineError> {
+    act(&app, &state, |player_id| {
+        GameEvent::Unequip(player_id, item_id)
+    })
+}
+
+#[specta::specta]
+#[tauri::command]
+fn use_item(app: AppHandle, state: GameStateWrapper, item_id: EntityId) -> Result<(), EngineError> {
+    act(&app, &state, |player_id| {
+        GameEvent::UseItem(player_id, item_id)
+    })
+}
+
+#[specta::specta]
+#[tauri::command]
+fn ascend(app: AppHandle, state: GameStateWrapper) -> Result<(), EngineError> {
+    act(&app, &state, GameEvent::Ascend)
+}
+
+#[specta::specta]
+#[tauri::command]
+fn descend(app: AppHandle, state: GameStateWrapper) -> Result<(), EngineError> {
+    act(&app, &state, GameEvent::Descend)
+}
+
+#[specta::specta]
+#[tauri::command]
+fn get_inventory(state: GameStateWrapper) -> Result<Vec<Entity>, EngineError> {
+    if let Ok(game) = state.lock() {
+        let player_id = game.state.world.player_id;
+
+        Ok(game
+            .state
+            .world
+            .get_inventory_items(player_id)
+            .into_iter()
+            .cloned()
+            .collect())
     } else {
-        Err("Error applying player move event".to_string())
+        Err(EngineError::StateUnavailable)
+    }
+}
+
+#[specta::specta]
+#[tauri::command]
+fn get_messages(state: GameStateWrapper) -> Result<Vec<Message>, EngineError> {
+    if let Ok(game) = state.lock() {
+        Ok(game
+            .state
+            .messages
+            .lock()
+            .messages()
+            .iter()
+            .cloned()
+            .collect())
+    } else {
+        Err(EngineError::StateUnavailable)
+    }
+}
+
+#[specta::specta]
+#[tauri::command]
+fn fire(app: AppHandle, state: GameStateWrapper, target: WorldPosition) -> Result<(), EngineError> {
+    act(&app, &state, |shooter| GameEvent::Fire { shooter, target })
+}
+
+#[specta::specta]
+#[tauri::command]
+fn throw_item(
+    app: AppHandle,
+    state: GameStateWrapper,
+    item_id: EntityId,
+    target: WorldPosition,
+) -> Result<(), EngineError> {
+    act(&app, &state, |thrower| GameEvent::Throw {
+        thrower,
+        item_id,
+        target,
+    })
+}
+
+#[specta::specta]
+#[tauri::command]
+fn cast(
+    app: AppHandle,
+    state: GameStateWrapper,
+    spell: SpellKind,
+    target: WorldPosition,
+) -> Result<(), EngineError> {
+    act(&app, &state, |player_id| {
+        GameEvent::Cast(player_id, spell, target)
+    })
+}
+
+#[specta::specta]
+#[tauri::command]
+fn preview_projectile(
+    state: GameStateWrapper,
+    target: WorldPosition,
+) -> Result<ProjectilePath, EngineError> {
+    if let Ok(game) = state.lock() {
+        let player_id = game.state.world.player_id;
+
+        game.state.preview_projectile(player_id, target)
+    } else {
+        Err(EngineError::StateUnavailable)
     }
 }
 
 #[cfg_attr(mobile, tauri::mobile_entry_point)]
 pub fn run() {
-    let state = Mutex::new(create_initial_game_state());
+    let builder = tauri_specta::Builder::<tauri::Wry>::new()
+        .commands(collect_commands![
+            get_game_state,
+            get_character_options,
+            validate_character,
+            new_game,
+            save_game,
+            load_game,
+            move_player,
+            attack,
+            open_door,
+            close_door,
+            rest,
+            pick_up,
+            drop_item,
+            equip,
+            unequip,
+            use_item,
+            get_inventory,
+            fire,
+            throw_item,
+            cast,
+            preview_projectile,
+            ascend,
+            descend,
+            get_messages
+        ])
+        .events(collect_events![WorldUpdated]);
 
-    tauri_specta::Builder::<tauri::Wry>::new()
-        .commands(collect_commands![get_game_state, move_player])
+    builder
         .export(
             Typescript::default().bigint(specta_typescript::BigIntExportBehavior::Number),
             "../src/bindings.ts",
@@ -63,8 +381,46 @@ pub fn run() {
 
     tauri::Builder::default()
         .plugin(tauri_plugin_opener::init())
-        .manage(state)
-        .invoke_handler(tauri::generate_handler![get_game_state, move_player])
+        .setup(move |app| {
+            builder.mount_events(app);
+            let log_dir = app.path().app_log_dir()?;
+            std::fs::create_dir_all(&log_dir)?;
+            app.manage(init_tracing(&log_dir));
+            tracing::info!(log_dir = %log_dir.display(), "logging started");
+
+            // Created once logging is up, so setting up the first game is logged too
+            app.manage(Mutex::new(Game {
+                state: create_game_state(&NewGameConfig::default())?,
+                sync: WorldSync::new(),
+            }));
+            Ok(())
+        })
+        .invoke_handler(tauri::generate_handler![
+            get_game_state,
+            get_character_options,
+            validate_character,
+            new_game,
+            save_game,
+            load_game,
+            move_player,
+            attack,
+            open_door,
+            close_door,
+            rest,
+            pick_up,
+            drop_item,
+            equip,
+            unequip,
+            use_item,
+            get_inventory,
+            fire,
+            throw_item,
+            cast,
+            preview_projectile,
+            ascend,
+            descend,
+            get_messages
+        ])
         .run(tauri::generate_context!())
         .expect("error while running tauri application");
 }
@@ -1,42 +1,86 @@
-import { useState, useEffect } from "react";
-import { invoke } from "@tauri-apps/api/core";
+import { useState, useEffect, useRef } from "react";
 import { GameRenderer } from "./components/GameRenderer";
 import "./App.css";
 import api from "./api";
-import { Entity } from "./bindings";
+import {
+  Direction,
+  Entity,
+  EntityDiff,
+  WorldDiff,
+  WorldSnapshot,
+} from "./bindings";
 
 function App() {
   const [entities, setEntities] = useState<Entity[]>([]);
+  const world = useRef(new Map<number, Entity>());
+  // The sequence number of the last update applied, or null while a snapshot is on its way.
+  const sequence = useRef<number | null>(null);
   const [selectedTile, setSelectedTile] = useState<{
     x: number;
     y: number;
   } | null>(null);
 
   useEffect(() => {
-    // Initial game state
-    updateGameState();
+    // Listen before asking for the snapshot so no update falls in between
+    const unlisten = api.onWorldUpdated((event) => applyDiff(event.payload));
+    resync();
+    return () => {
+      unlisten.then((stop) => stop());
+    };
   }, []);
 
-  const updateGameState = async () => {
-    const state = await api.getGameState();
-    if (state.status === "ok") {
-      setEntities(Object.values(state.data.world.entities).filter((x) => !!x));
+  const resync = async () => {
+    sequence.current = null;
+    const snapshot = await api.getGameState();
+    if (snapshot.status === "ok") {
+      applySnapshot(snapshot.data);
     }
   };
 
+  const applySnapshot = (snapshot: WorldSnapshot) => {
+    world.current = new Map(
+      Object.values(snapshot.view.entities)
+        .filter((x) => !!x)
+        .map((entity) => [entity.id, entity]),
+    );
+    sequence.current = snapshot.sequence;
+    setEntities([...world.current.values()]);
+  };
+
+  const applyDiff = (diff: WorldDiff) => {
+    if (sequence.current === null || diff.sequence <= sequence.current) {
+      // Already covered by the snapshot being loaded or applied
+      return;
+    }
+    if (diff.sequence !== sequence.current + 1) {
+      // An update went missing, so start over from a fresh snapshot
+      resync();
+      return;
+    }
+
+    diff.entities.forEach((change: EntityDiff) => {
+      if (change.type === "Removed") {
+        world.current.delete(change.entity_id);
+      } else {
+        world.current.set(change.entity.id, change.entity);
+      }
+    });
+    sequence.current = diff.sequence;
+    setEntities([...world.current.values()]);
+  };
+
   const handleTileClick = async (x: number, y: number) => {
     setSelectedTile({ x, y });
-    const entitiesAtPos = await invoke<Entity[]>("get_entities_at_position", {
-      x,
-      y,
-    });
+    const entitiesAtPos = [...world.current.values()].filter(
+      (entity) => entity.pos?.x === x && entity.pos?.y === y,
+    );
     if (entitiesAtPos.length > 0) {
       console.log("Selected entities:", entitiesAtPos);
     }
   };
 
   const handleKeyPress = async (event: KeyboardEvent) => {
-    let direction = null;
+    let direction: Direction | null = null;
     switch (event.key) {
       case "h":
         direction = "West";
@@ -53,8 +97,53 @@ function App() {
     }
 
     if (direction) {
-      await invoke("move_player", { direction });
-      await updateGameState();
+      await api.movePlayer(direction);
+      return;
+    }
+
+    switch (event.key) {
+      case ".":
+        await api.rest();
+        break;
+      case "<":
+        await api.ascend();
+        break;
+      case ">":
+        await api.descend();
+        break;
+      case "g":
+        await pickUpHere();
+        break;
+      case "S":
+        await api.saveGame();
+        break;
+      case "L": {
+        const loaded = await api.loadGame();
+        if (loaded.status === "ok") applySnapshot(loaded.data);
+        break;
+      }
+      case "N": {
+        const options = await api.getCharacterOptions();
+        const started = await api.newGame(options.defaults);
+        if (started.status === "ok") applySnapshot(started.data);
+        break;
+      }
+    }
+  };
+
+  // Picks up the first item lying where the player stands
+  const pickUpHere = async () => {
+    const player = [...world.current.values()].find(
+      (entity) => entity.kind.type === "Player",
+    );
+    const item = [...world.current.values()].find(
+      (entity) =>
+        entity.kind.type === "Item" &&
+        entity.pos?.x === player?.pos?.x &&
+        entity.pos?.y === player?.pos?.y,
+    );
+    if (item) {
+      await api.pickUp(item.id);
     }
   };
 
@@ -1,6 +1,42 @@
-import { commands, Direction } from "./bindings";
+import {
+  commands,
+  events,
+  Direction,
+  Hand,
+  NewGameConfig,
+  SpellKind,
+  WorldPosition,
+} from "./bindings";
 
 export default {
   getGameState: commands.getGameState,
+  getCharacterOptions: commands.getCharacterOptions,
+  validateCharacter: (config: NewGameConfig) =>
+    commands.validateCharacter(config),
+  newGame: (config: NewGameConfig) => commands.newGame(config),
+  saveGame: commands.saveGame,
+  loadGame: commands.loadGame,
   movePlayer: (direction: Direction) => commands.movePlayer(direction),
+  attack: (direction: Direction) => commands.attack(direction),
+  openDoor: (direction: Direction) => commands.openDoor(direction),
+  closeDoor: (direction: Direction) => commands.closeDoor(direction),
+  rest: commands.rest,
+  pickUp: (itemId: number) => commands.pickUp(itemId),
+  dropItem: (itemId: number) => commands.dropItem(itemId),
+  equip: (itemId: number, hand: Hand | null = null) =>
+    commands.equip(itemId, hand),
+  unequip: (itemId: number) => commands.unequip(itemId),
+  useItem: (itemId: number) => commands.useItem(itemId),
+  getInventory: commands.getInventory,
+  fire: (target: WorldPosition) => commands.fire(target),
+  throwItem: (itemId: number, target: WorldPosition) =>
+    commands.throwItem(itemId, target),
+  cast: (spell: SpellKind, target: WorldPosition) =>
+    commands.cast(spell, target),
+  previewProjectile: (target: WorldPosition) =>
+    commands.previewProjectile(target),
+  ascend: commands.ascend,
+  descend: commands.descend,
+  getMessages: commands.getMessages,
+  onWorldUpdated: events.worldUpdated.listen,
 };
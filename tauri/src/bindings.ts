@@ -5,7 +5,10 @@
 
 
 export const commands = {
-async getGameState() : Promise<Result<ClientGameState, string>> {
+/**
+ * Sends the player's whole view. Used when the frontend loads or has missed an update.
+ */
+async getGameState() : Promise<Result<WorldSnapshot, EngineError>> {
     try {
     return { status: "ok", data: await TAURI_INVOKE("get_game_state") };
 } catch (e) {
@@ -13,19 +16,212 @@ async getGameState() : Promise<Result<ClientGameState, string>> {
     else return { status: "error", error: e  as any };
 }
 },
-async movePlayer(direction: Direction) : Promise<Result<ClientGameState, string>> {
+/**
+ * The species, kits and difficulties to choose from on the character creation screen.
+ */
+async getCharacterOptions() : Promise<CharacterOptions> {
+    return await TAURI_INVOKE("get_character_options");
+},
+/**
+ * Checks the choices made so far, so the character creation screen can point
+ * out what's wrong before a game is started.
+ */
+async validateCharacter(config: NewGameConfig) : Promise<Result<null, EngineError>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("validate_character", { config }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Throws away the current game and starts a new one from the choices made
+ * for it. A seed is picked if none is given.
+ */
+async newGame(config: NewGameConfig) : Promise<Result<WorldSnapshot, EngineError>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("new_game", { config }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+async saveGame() : Promise<Result<null, EngineError>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("save_game") };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Replaces the current game with the saved one.
+ */
+async loadGame() : Promise<Result<WorldSnapshot, EngineError>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("load_game") };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+async movePlayer(direction: Direction) : Promise<Result<null, EngineError>> {
     try {
     return { status: "ok", data: await TAURI_INVOKE("move_player", { direction }) };
 } catch (e) {
     if(e instanceof Error) throw e;
     else return { status: "error", error: e  as any };
 }
+},
+async attack(direction: Direction) : Promise<Result<null, EngineError>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("attack", { direction }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+async openDoor(direction: Direction) : Promise<Result<null, EngineError>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("open_door", { direction }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+async closeDoor(direction: Direction) : Promise<Result<null, EngineError>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("close_door", { direction }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+async rest() : Promise<Result<null, EngineError>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("rest") };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+async pickUp(itemId: number) : Promise<Result<null, EngineError>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("pick_up", { itemId }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+async dropItem(itemId: number) : Promise<Result<null, EngineError>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("drop_item", { itemId }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Equips a carried item. Weapons go in `hand` if one is given.
+ */
+async equip(itemId: number, hand: Hand | null) : Promise<Result<null, EngineError>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("equip", { itemId, hand }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+async unequip(itemId: number) : Promise<Result<null, EngineError>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("unequip", { itemId }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+async useItem(itemId: number) : Promise<Result<null, EngineError>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("use_item", { itemId }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+async getInventory() : Promise<Result<Entity[], EngineError>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("get_inventory") };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+async fire(target: WorldPosition) : Promise<Result<null, EngineError>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("fire", { target }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+async throwItem(itemId: number, target: WorldPosition) : Promise<Result<null, EngineError>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("throw_item", { itemId, target }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+async cast(spell: SpellKind, target: WorldPosition) : Promise<Result<null, EngineError>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("cast", { spell, target }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+async previewProjectile(target: WorldPosition) : Promise<Result<ProjectilePath, EngineError>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("preview_projectile", { target }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+async ascend() : Promise<Result<null, EngineError>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("ascend") };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+async descend() : Promise<Result<null, EngineError>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("descend") };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+async getMessages() : Promise<Result<Message[], EngineError>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("get_messages") };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
 }
 }
 
 /** user-defined events **/
 
 
+export const events = __makeEvents__<{
+worldUpdated: WorldUpdated
+}>({
+worldUpdated: "world-updated"
+})
 
 /** user-defined constants **/
 
@@ -34,25 +230,219 @@ async movePlayer(direction: Direction) : Promise<Result<ClientGameState, string>
 /** user-defined types **/
 
 export type Ai = { memory: Memory }
-export type ClientGameState = { world: World }
+/**
+ * How the player's attributes are decided.
+ */
+export type AttributeChoice = 
+/**
+ * Attributes bought with up to [`POINT_BUY_BUDGET`] points.
+ */
+{ type: "PointBuy"; attributes: CoreAttributes } | 
+/**
+ * Every attribute is rolled as 4d6, keeping the highest three, and
+ * shifted by how far the species' template strays from 10 in it.
+ */
+{ type: "Rolled" }
+/**
+ * Changes to core attributes from a buff or debuff.
+ */
+export type AttributeModifier = { strength: number; speed: number; durability: number; fortitude: number; magic: number }
+export type Armor = { body: number | null }
+/**
+ * The choices there are when creating a character, for building a character
+ * creation screen.
+ */
+export type CharacterOptions = { species: SpeciesKind[]; kits: StartingKit[]; difficulties: Difficulty[]; point_buy_budget: number; min_attribute: number; max_attribute: number; 
+/**
+ * A valid config to start the choices from.
+ */
+defaults: NewGameConfig }
 export type CoreAttributes = { strength: number; speed: number; durability: number; fortitude: number; magic: number }
 export type Damage = { damage_type: DamageType; damage: DieRoll }
-export type DamageType = "Slice" | "Pierce" | "Blunt" | "Fire"
+export type DamageType = "Slice" | "Pierce" | "Blunt" | "Fire" | "Poison"
 export type Dice = "D4" | "D6" | "D8" | "D10" | "D12" | "D20" | "D100" | { Flat: number }
-export type DieRoll = { count: number; dice: Dice; modifier: number }
+/**
+ * A dice expression such as `2d6+3`, `4d6kh3` or `1d6!`.
+ */
+export type DieRoll = { count: number; dice: Dice; modifier: number; 
+/**
+ * Only the highest this many dice count towards the result.
+ */
+keep_highest: number | null; 
+/**
+ * A die that rolls its highest face is rolled again and added.
+ */
+exploding: boolean }
+export type Difficulty = "Easy" | "Normal" | "Hard"
 export type Direction = "North" | "East" | "South" | "West"
-export type Entity = { id: number; kind: EntityKind; pos: WorldPosition | null; stats: CoreAttributes; status: Status; visible: boolean; discovered: boolean; ai: Ai | null }
-export type EntityKind = { type: "Player" } | { type: "Npc"; species: SpeciesKind } | { type: "Item"; kind: ItemKind } | { type: "Wall"; material: Material } | { type: "Floor"; material: Material }
+export type EffectKind = { type: "Poison"; damage_per_turn: number } | { type: "Burning"; damage_per_turn: number } | { type: "Stun" } | { type: "Regeneration"; health_per_turn: number } | { type: "Modifier"; modifier: AttributeModifier }
+/**
+ * Why the engine refused to carry out an action.
+ */
+export type EngineError = { type: "EntityNotFound"; entity_id: number } | 
+/**
+ * The action can't be taken in the current situation.
+ */
+{ type: "ActionNotAllowed"; reason: string } | { type: "NotEntitysTurn"; entity_id: number; 
+/**
+ * Whoever's turn it actually is, if anyone's.
+ */
+current: number | null } | 
+/**
+ * The entity or position the action was aimed at can't be used for it.
+ */
+{ type: "InvalidTarget"; reason: string } | 
+/**
+ * The game state couldn't be accessed at all.
+ */
+{ type: "StateUnavailable" } | 
+/**
+ * The game couldn't be written out.
+ */
+{ type: "SaveFailed"; reason: string } | 
+/**
+ * A saved game couldn't be read back.
+ */
+{ type: "InvalidSave"; reason: string } | 
+/**
+ * The choices for a new game don't make a valid game or character.
+ */
+{ type: "InvalidConfig"; reason: string }
+/**
+ * Something in the world. Besides its kind, an entity is made up of optional
+ * components, so it only carries the parts that make sense for it. See
+ * [`super::components`].
+ */
+export type Entity = { id: number; kind: EntityKind; pos: WorldPosition | null; 
+/**
+ * Attributes, for creatures and terrain that can be worn down. `None` for
+ * everything else, and for creatures the player can only see from outside.
+ */
+stats: CoreAttributes | null; 
+/**
+ * Health, stamina and mana. `None` for entities that can't be hurt, such
+ * as floors and items.
+ */
+status: Status | null; visible: boolean; discovered: boolean; ai: Ai | null; inventory: Inventory | null; equipment: Equipment | null; effects: StatusEffect[]; spells: SpellKind[] }
+/**
+ * What happened to a single entity since the last update.
+ */
+export type EntityDiff = { type: "Added"; entity: Entity } | { type: "Changed"; entity: Entity } | { type: "Removed"; entity_id: number }
+export type EntityKind = { type: "Player"; species: SpeciesKind } | { type: "Npc"; species: SpeciesKind } | { type: "Item"; kind: ItemKind } | { type: "Wall"; material: Material } | { type: "Floor"; material: Material } | { type: "Door"; material: Material; open: boolean; 
+/**
+ * The key needed to unlock the door, if it is locked.
+ */
+lock: number | null } | { type: "Stairs"; material: Material; direction: StairDirection }
+export type Equipment = { armor: Armor; hands: HandsEquipment }
 export type Exhaustion = "WellRested" | "Rested" | "Normal" | "Tired" | "Exhausted"
-export type ItemKind = { Weapon: { damage: Damage[] } } | { Armor: { defense: number } }
+export type Hand = "Left" | "Right"
+export type HandsEquipment = { TwoHanded: number | null } | { OneHanded: { left: number | null; right: number | null } }
+export type Inventory = { items: number[] }
+export type ItemKind = { Weapon: { damage: Damage[]; two_handed: boolean; 
+/**
+ * How far the weapon shoots, or `None` for melee weapons.
+ */
+range: number | null } } | { Armor: { defense: number } } | { Key: { key_id: number } }
 export type LastSeen = { entity: number; position: WorldPosition; on_turn: number }
 export type Material = { kind: MaterialKind; blocks_vision: boolean; blocks_movement: boolean }
-export type MaterialKind = "Stone" | "Flesh"
+export type MaterialKind = "Stone" | "Wood" | "Flesh"
 export type Memory = { last_seen_positions: LastSeen[] }
+export type Message = { turn: number; severity: Severity; 
+/**
+ * The template the message was built from.
+ */
+key: string; 
+/**
+ * The values filled into the template's `{placeholders}`.
+ */
+args: { [key in string]: string }; 
+/**
+ * The message rendered in the log's language.
+ */
+text: string }
+/**
+ * Everything chosen when starting a new game.
+ */
+export type NewGameConfig = { 
+/**
+ * The seed to start from, or `None` to pick one at random.
+ */
+seed: number | null; species: SpeciesKind; attributes: AttributeChoice; kit: StartingKit; difficulty: Difficulty }
+/**
+ * The world as the player knows it: the player and whatever they carry in
+ * full, what is in sight right now, and the terrain they remember. Everything
+ * else, such as creatures out of sight or what goes on in an AI's head, is
+ * left out so the client can't show it.
+ */
+export type PlayerView = { player_id: number; depth: number; entities: Partial<{ [key in number]: Entity }> }
+export type Pool = { current: number; max: number }
+/**
+ * The route a projectile takes and what it runs into.
+ */
+export type ProjectilePath = { 
+/**
+ * Every tile the projectile passes through, not counting where it was launched from.
+ */
+path: WorldPosition[]; 
+/**
+ * The creature or obstacle that stopped the projectile, if any.
+ */
+hit: number | null; 
+/**
+ * Where the projectile comes to rest.
+ */
+end: WorldPosition }
+/**
+ * How far each pool is towards regenerating its next point. Every turn an
+ * entity takes adds the pool's attribute, and every [`REGEN_BASE`] points
+ * add up to one point regenerated. Counting per entity keeps the rate the
+ * same no matter how many others take turns in between.
+ */
+export type Regeneration = { health: number; stamina: number; mana: number }
+export type Severity = "Info" | "Good" | "Warning" | "Danger"
 export type SpeciesKind = "Human" | "Goblin"
-export type Status = { health: number; stamina: number; mana: number; exhaustion: Exhaustion }
-export type World = { entities: Partial<{ [key in number]: Entity }>; next_entity_id: number; player_id: number }
+export type SpellKind = "Firebolt" | "Fireball" | "FrostCone" | "FlameLance" | "Daze" | "Weaken" | "Mend"
+export type StairDirection = "Up" | "Down"
+/**
+ * What the player sets out with.
+ */
+export type StartingKit = 
+/**
+ * A dagger and leather armor.
+ */
+"Fighter" | 
+/**
+ * A shortbow.
+ */
+"Archer" | 
+/**
+ * A few spells and nothing else.
+ */
+"Mage"
+export type Status = { health: Pool; stamina: Pool; mana: Pool; exhaustion: Exhaustion; 
+/**
+ * Accumulated tiredness. Acting raises it, resting lowers it.
+ */
+fatigue: number; regeneration: Regeneration }
+export type StatusEffect = { kind: EffectKind; remaining_turns: number; stacks: number; 
+/**
+ * Whoever caused the effect, if anyone.
+ */
+source: number | null }
+export type WorldDiff = { sequence: number; depth: number; entities: EntityDiff[] }
 export type WorldPosition = { x: number; y: number }
+/**
+ * The player's whole view, for a client that is starting out or has fallen behind.
+ */
+export type WorldSnapshot = { 
+/**
+ * The next diff will be numbered one higher than this.
+ */
+sequence: number; view: PlayerView }
+/**
+ * Sent after every player action with whatever changed in the world.
+ */
+export type WorldUpdated = WorldDiff
 
 /** tauri-specta globals **/
 
@@ -51,6 +51,12 @@ export const GameRenderer = ({
             case "Wall":
               g.beginFill(0x808080);
               break;
+            case "Stairs":
+              g.beginFill(0xffd700);
+              break;
+            case "Door":
+              g.beginFill(entity.kind.open ? 0xc8a064 : 0x8b5a2b);
+              break;
             default:
               g.beginFill(0xffffff);
           }